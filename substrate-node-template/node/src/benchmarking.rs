@@ -4,17 +4,20 @@
 
 use crate::service::FullClient;
 
+use frame_support::traits::Get;
 use node_template_runtime as runtime;
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use runtime::{pallet_poe, AccountId, Balance, BalancesCall, Runtime, SystemCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
-use sp_core::{Encode, Pair};
+use sp_core::{hashing::blake2_256, sr25519, Encode, Pair};
 use sp_inherents::{InherentData, InherentDataProvider};
 use sp_keyring::Sr25519Keyring;
 use sp_runtime::{OpaqueExtrinsic, SaturatedConversion};
 
 use std::{sync::Arc, time::Duration};
 
+type PoeCall = runtime::pallet_poe::Call<Runtime>;
+
 /// Generates extrinsics for the `benchmark overhead` command.
 ///
 /// Note: Should only be used for benchmarking.
@@ -92,6 +95,171 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 	}
 }
 
+/// Signers of the PoE benchmark extrinsics: the well-known development accounts, all of which
+/// are endowed in the `dev` and `local` chains.
+///
+/// The runtime lets an account create at most `MaxClaimsPerAccount` claims in a window, so a
+/// single sender could not fill a block.
+fn poe_signers() -> Vec<sr25519::Pair> {
+	use Sr25519Keyring::*;
+
+	let keys = [Alice, Bob, Charlie, Dave, Eve, Ferdie];
+	let stashes = keys.iter().map(|key| {
+		sr25519::Pair::from_string(&format!("{}//stash", key.to_seed()), None)
+			.expect("Static values are valid; qed")
+	});
+	keys.iter().map(|key| key.pair()).chain(stashes).collect()
+}
+
+/// Claims that are unique for every `index` and across builders.
+struct ClaimGenerator {
+	seed: [u8; 32],
+	length: usize,
+}
+
+impl ClaimGenerator {
+	fn new(length: usize) -> std::result::Result<Self, String> {
+		let max_length = <Runtime as pallet_poe::Config>::MaxClaimLength::get() as usize;
+		if length == 0 || length > max_length {
+			return Err(format!("Claim length must be between 1 and {}", max_length))
+		}
+		Ok(Self { seed: rand::random(), length })
+	}
+
+	fn claim(&self, index: u32) -> Vec<u8> {
+		let mut claim = Vec::with_capacity(self.length);
+		let mut chunk = 0u32;
+		while claim.len() < self.length {
+			claim.extend(blake2_256(&(self.seed, index, chunk).encode()));
+			chunk += 1;
+		}
+		claim.truncate(self.length);
+		claim
+	}
+}
+
+/// Generates `PoeModule::create_claim` extrinsics with a new random claim for every nonce.
+///
+/// Note: Should only be used for benchmarking.
+pub struct CreateClaimBuilder {
+	client: Arc<FullClient>,
+	signers: Vec<sr25519::Pair>,
+	claims: ClaimGenerator,
+}
+
+impl CreateClaimBuilder {
+	/// Creates a new [`Self`] from the given client, creating claims of `claim_length` bytes.
+	pub fn new(client: Arc<FullClient>, claim_length: usize) -> Result<Self> {
+		Ok(Self { client, signers: poe_signers(), claims: ClaimGenerator::new(claim_length)? })
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for CreateClaimBuilder {
+	fn pallet(&self) -> &str {
+		"poe"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"create_claim"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		// 轮流使用各个账户签名，`nonce` 是整个区块的序号
+		let signers = self.signers.len() as u32;
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			self.signers[(nonce % signers) as usize].clone(),
+			PoeCall::create_claim { claim: self.claims.claim(nonce) }.into(),
+			nonce / signers,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
+/// Operation on an existing claim benchmarked by [`ClaimOperationBuilder`].
+#[derive(Clone, Copy)]
+enum ClaimOperation {
+	Transfer,
+	Revoke,
+}
+
+/// Generates `PoeModule::transfer_claim` or `PoeModule::revoke_claim` extrinsics.
+///
+/// The claims have to exist first, so every even nonce creates a claim and the following odd
+/// nonce transfers or revokes it. The time reported per extrinsic is thus the average of
+/// `create_claim` and the benchmarked call; subtract half of the `create_claim` result to get
+/// the cost of the call alone.
+///
+/// Note: Should only be used for benchmarking.
+pub struct ClaimOperationBuilder {
+	client: Arc<FullClient>,
+	signers: Vec<sr25519::Pair>,
+	claims: ClaimGenerator,
+	operation: ClaimOperation,
+}
+
+impl ClaimOperationBuilder {
+	/// Creates a builder of `transfer_claim` extrinsics on claims of `claim_length` bytes.
+	pub fn transfer(client: Arc<FullClient>, claim_length: usize) -> Result<Self> {
+		Self::new(client, claim_length, ClaimOperation::Transfer)
+	}
+
+	/// Creates a builder of `revoke_claim` extrinsics on claims of `claim_length` bytes.
+	pub fn revoke(client: Arc<FullClient>, claim_length: usize) -> Result<Self> {
+		Self::new(client, claim_length, ClaimOperation::Revoke)
+	}
+
+	fn new(
+		client: Arc<FullClient>,
+		claim_length: usize,
+		operation: ClaimOperation,
+	) -> Result<Self> {
+		let claims = ClaimGenerator::new(claim_length)?;
+		Ok(Self { client, signers: poe_signers(), claims, operation })
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for ClaimOperationBuilder {
+	fn pallet(&self) -> &str {
+		"poe"
+	}
+
+	fn extrinsic(&self) -> &str {
+		match self.operation {
+			ClaimOperation::Transfer => "transfer_claim",
+			ClaimOperation::Revoke => "revoke_claim",
+		}
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		let signers = self.signers.len() as u32;
+		let pair = nonce / 2;
+		let signer = (pair % signers) as usize;
+		let claim = self.claims.claim(pair);
+
+		let call = match (nonce % 2, self.operation) {
+			(0, _) => PoeCall::create_claim { claim },
+			(_, ClaimOperation::Transfer) => {
+				let dest = self.signers[(signer + 1) % self.signers.len()].public();
+				PoeCall::transfer_claim { claim, dest: dest.into() }
+			},
+			(_, ClaimOperation::Revoke) => PoeCall::revoke_claim { claim },
+		};
+		// 每个账户依次发送一对交易
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			self.signers[signer].clone(),
+			call.into(),
+			pair / signers * 2 + nonce % 2,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
 /// Create a transaction using the given `call`.
 ///
 /// Note: Should only be used for benchmarking.
@@ -102,9 +270,48 @@ pub fn create_benchmark_extrinsic(
 	nonce: u32,
 ) -> runtime::UncheckedExtrinsic {
 	let genesis_hash = client.block_hash(0).ok().flatten().expect("Genesis block exists; qed");
-	let best_hash = client.chain_info().best_hash;
-	let best_block = client.chain_info().best_number;
+	let context = SigningContext {
+		genesis_hash,
+		best_hash: client.chain_info().best_hash,
+		best_number: client.chain_info().best_number,
+		spec_version: runtime::VERSION.spec_version,
+		transaction_version: runtime::VERSION.transaction_version,
+	};
 
+	let raw_payload = signed_payload(&context, call, nonce);
+	let signature = raw_payload.using_encoded(|e| sender.sign(e));
+	let (call, extra, _) = raw_payload.deconstruct();
+
+	runtime::UncheckedExtrinsic::new_signed(
+		call,
+		sp_runtime::AccountId32::from(sender.public()).into(),
+		runtime::Signature::Sr25519(signature),
+		extra,
+	)
+}
+
+/// The chain a signed transaction is built for.
+pub struct SigningContext {
+	/// Hash of the chain's genesis block.
+	pub genesis_hash: runtime::Hash,
+	/// Hash of the block the transaction's mortality starts at.
+	pub best_hash: runtime::Hash,
+	/// Number of the block the transaction's mortality starts at.
+	pub best_number: runtime::BlockNumber,
+	/// Spec version of the runtime the transaction is for.
+	pub spec_version: u32,
+	/// Transaction version of the runtime the transaction is for.
+	pub transaction_version: u32,
+}
+
+/// Build the payload the sender signs to submit `call` with `nonce`.
+///
+/// Also used by the `poe` commands to sign transactions for a running node.
+pub fn signed_payload(
+	context: &SigningContext,
+	call: runtime::RuntimeCall,
+	nonce: u32,
+) -> runtime::SignedPayload {
 	let period = runtime::BlockHashCount::get()
 		.checked_next_power_of_two()
 		.map(|c| c / 2)
@@ -116,34 +323,28 @@ pub fn create_benchmark_extrinsic(
 		frame_system::CheckGenesis::<runtime::Runtime>::new(),
 		frame_system::CheckEra::<runtime::Runtime>::from(sp_runtime::generic::Era::mortal(
 			period,
-			best_block.saturated_into(),
+			context.best_number.saturated_into(),
 		)),
 		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
 		frame_system::CheckWeight::<runtime::Runtime>::new(),
 		pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+		runtime::pallet_poe::ValidateClaim::<runtime::Runtime>::new(),
 	);
 
-	let raw_payload = runtime::SignedPayload::from_raw(
-		call.clone(),
-		extra.clone(),
+	runtime::SignedPayload::from_raw(
+		call,
+		extra,
 		(
 			(),
-			runtime::VERSION.spec_version,
-			runtime::VERSION.transaction_version,
-			genesis_hash,
-			best_hash,
+			context.spec_version,
+			context.transaction_version,
+			context.genesis_hash,
+			context.best_hash,
+			(),
 			(),
 			(),
 			(),
 		),
-	);
-	let signature = raw_payload.using_encoded(|e| sender.sign(e));
-
-	runtime::UncheckedExtrinsic::new_signed(
-		call,
-		sp_runtime::AccountId32::from(sender.public()).into(),
-		runtime::Signature::Sr25519(signature),
-		extra,
 	)
 }
 
@@ -1,12 +1,23 @@
+use crate::poe;
+use frame_support::traits::Get;
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GrandpaConfig, RuntimeGenesisConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	pallet_poe::{self, ClaimValidator, PoeParameters},
+	AccountId, AuraConfig, Balance, BalancesConfig, BlockNumber, GrandpaConfig,
+	NftsCollectionDeposit, NftsItemDeposit, PoeModuleConfig, PoeNftOwner, Runtime,
+	RuntimeGenesisConfig, SS58Prefix, Signature, SudoConfig, SystemConfig, EXISTENTIAL_DEPOSIT,
+	WASM_BINARY,
 };
-use sc_service::ChainType;
+use sc_network::config::MultiaddrWithPeerId;
+use sc_service::{ChainSpec as _, ChainType, Properties};
+use serde::Deserialize;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
-use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_core::{crypto::Ss58Codec, ed25519, sr25519, Pair, Public};
+use sp_runtime::{
+	traits::{IdentifyAccount, Verify},
+	BuildStorage,
+};
+use std::{collections::BTreeSet, fs, path::Path};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -36,10 +47,36 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// Token properties shown by wallets and explorers.
+fn properties(token_symbol: &str, token_decimals: u8, ss58_format: u16) -> Properties {
+	let mut properties = Properties::new();
+	properties.insert("tokenSymbol".into(), token_symbol.into());
+	properties.insert("tokenDecimals".into(), token_decimals.into());
+	properties.insert("ss58Format".into(), ss58_format.into());
+	properties
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	// Pre-funded accounts, enough of them to fill a block with claims without hitting
+	// `MaxClaimsPerAccount`
+	let endowed_accounts = vec![
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		get_account_id_from_seed::<sr25519::Public>("Bob"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie"),
+		get_account_id_from_seed::<sr25519::Public>("Dave"),
+		get_account_id_from_seed::<sr25519::Public>("Eve"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+		get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+	];
+	let genesis_accounts = endowed_accounts.clone();
 
-	Ok(ChainSpec::from_genesis(
+	let spec = ChainSpec::from_genesis(
 		// Name
 		"Development",
 		// ID
@@ -52,13 +89,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				vec![authority_keys_from_seed("Alice")],
 				// Sudo account
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
-				// Pre-funded accounts
-				vec![
-					get_account_id_from_seed::<sr25519::Public>("Alice"),
-					get_account_id_from_seed::<sr25519::Public>("Bob"),
-					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-				],
+				genesis_accounts.clone(),
 				true,
 			)
 		},
@@ -68,18 +99,36 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		None,
 		// Protocol ID
 		None,
+		// Fork ID
 		None,
 		// Properties
-		None,
+		Some(properties("UNIT", 12, SS58Prefix::get().into())),
 		// Extensions
 		None,
-	))
+	);
+	with_dev_identities(spec, &endowed_accounts)
 }
 
 pub fn local_testnet_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	// Pre-funded accounts
+	let endowed_accounts = vec![
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		get_account_id_from_seed::<sr25519::Public>("Bob"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie"),
+		get_account_id_from_seed::<sr25519::Public>("Dave"),
+		get_account_id_from_seed::<sr25519::Public>("Eve"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+		get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+	];
+	let genesis_accounts = endowed_accounts.clone();
 
-	Ok(ChainSpec::from_genesis(
+	let spec = ChainSpec::from_genesis(
 		// Name
 		"Local Testnet",
 		// ID
@@ -92,21 +141,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
 				// Sudo account
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
-				// Pre-funded accounts
-				vec![
-					get_account_id_from_seed::<sr25519::Public>("Alice"),
-					get_account_id_from_seed::<sr25519::Public>("Bob"),
-					get_account_id_from_seed::<sr25519::Public>("Charlie"),
-					get_account_id_from_seed::<sr25519::Public>("Dave"),
-					get_account_id_from_seed::<sr25519::Public>("Eve"),
-					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-				],
+				genesis_accounts.clone(),
 				true,
 			)
 		},
@@ -116,14 +151,304 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		None,
 		// Protocol ID
 		None,
+		// Fork ID
+		None,
 		// Properties
+		Some(properties("UNIT", 12, SS58Prefix::get().into())),
+		// Extensions
+		None,
+	);
+	with_dev_identities(spec, &endowed_accounts)
+}
+
+/// Make Alice the `PoeRegistrar` of a development chain and give `accounts` identities it judged
+/// `KnownGood`, so that they can hold claims from genesis.
+fn with_dev_identities(mut spec: ChainSpec, accounts: &[AccountId]) -> Result<ChainSpec, String> {
+	let mut storage = spec.build_storage()?;
+	let registrar = get_account_id_from_seed::<sr25519::Public>("Alice");
+	storage.top.extend(poe::verified_identities(registrar, accounts)?);
+	spec.set_storage(storage);
+	Ok(spec)
+}
+
+/// Settings of a chain built from a TOML or JSON file with [`config_file_config`] or
+/// [`staging_config`]. Accounts and keys are given as SS58 addresses.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NetworkConfig {
+	/// Human readable name of the chain.
+	pub name: String,
+	/// Identifier of the chain, naming its directory in the base path.
+	pub id: String,
+	/// Type of the chain, `Local` if not given.
+	pub chain_type: Option<ChainType>,
+	/// Network protocol id, to keep the chain's nodes apart from other chains.
+	pub protocol_id: Option<String>,
+	/// Addresses of the nodes to connect to first, with their peer ids.
+	#[serde(default)]
+	pub boot_nodes: Vec<String>,
+	/// Symbol of the native token.
+	pub token_symbol: String,
+	/// Number of decimals of the native token.
+	pub token_decimals: u8,
+	/// Address format shown by wallets, the runtime's `SS58Prefix` if not given.
+	pub ss58_format: Option<u16>,
+	/// Initial Aura and GRANDPA keys of the block authors.
+	pub authorities: Vec<AuthorityKeys>,
+	/// Account allowed to dispatch calls as root.
+	pub sudo: String,
+	/// Accounts funded at genesis.
+	#[serde(default)]
+	pub endowed_accounts: Vec<EndowedAccount>,
+	/// Claims existing from the genesis block on.
+	#[serde(default)]
+	pub claims: Vec<GenesisClaim>,
+	/// Initial PoE parameters, the upper bounds of the runtime if not given.
+	pub poe_parameters: Option<PoeParameters<BlockNumber>>,
+}
+
+/// Session keys of a block author.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AuthorityKeys {
+	/// Sr25519 key authoring blocks.
+	pub aura: String,
+	/// Ed25519 key voting on finality.
+	pub grandpa: String,
+}
+
+/// An account funded at genesis.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EndowedAccount {
+	/// The funded account.
+	pub account: String,
+	/// Initial free balance, in the smallest unit of the token.
+	pub balance: BalanceValue,
+}
+
+/// A balance given as a number, or as a string when it does not fit into a TOML or JSON integer.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum BalanceValue {
+	/// A balance that fits into 64 bits.
+	Number(u64),
+	/// A balance in decimal digits.
+	Text(String),
+}
+
+/// A claim existing from the genesis block on.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GenesisClaim {
+	/// The claim, hex encoded.
+	pub claim: String,
+	/// The owner of the claim.
+	pub owner: String,
+}
+
+/// Genesis state decoded from a [`NetworkConfig`].
+#[derive(Clone)]
+struct GenesisParams {
+	authorities: Vec<(AuraId, GrandpaId)>,
+	root_key: AccountId,
+	balances: Vec<(AccountId, Balance)>,
+	claims: Vec<(Vec<u8>, AccountId)>,
+	poe_parameters: Option<PoeParameters<BlockNumber>>,
+}
+
+/// Seeds of the well-known development accounts, whose keys anybody can sign with.
+const DEV_SEEDS: [&str; 6] = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// Build a chain spec from the config file at `path`, in TOML if its extension is `.toml` and
+/// in JSON otherwise.
+///
+/// Live chains must not use the keys of development accounts.
+pub fn config_file_config(path: &Path) -> Result<ChainSpec, String> {
+	let config = read_network_config(path)?;
+	let chain_type = config.chain_type.clone().unwrap_or(ChainType::Local);
+	network_config_spec(config, chain_type)
+}
+
+/// Build the spec of a live chain from the config file at `path`, refusing the keys of
+/// development accounts.
+pub fn staging_config(path: &Path) -> Result<ChainSpec, String> {
+	let config = read_network_config(path)?;
+	match &config.chain_type {
+		None | Some(ChainType::Live) => network_config_spec(config, ChainType::Live),
+		Some(chain_type) => Err(format!("A staging chain must be Live, not {:?}", chain_type)),
+	}
+}
+
+fn read_network_config(path: &Path) -> Result<NetworkConfig, String> {
+	let content = fs::read_to_string(path)
+		.map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+
+	if path.extension().map_or(false, |extension| extension == "toml") {
+		toml::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+	} else {
+		serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+	}
+}
+
+fn network_config_spec(config: NetworkConfig, chain_type: ChainType) -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Wasm binary not available".to_string())?;
+	let genesis = genesis_params(&config)?;
+	if chain_type == ChainType::Live {
+		ensure_no_dev_keys(&genesis)?;
+	}
+
+	let boot_nodes = config
+		.boot_nodes
+		.iter()
+		.map(|node| node.parse().map_err(|e| format!("Invalid boot node {}: {}", node, e)))
+		.collect::<Result<Vec<MultiaddrWithPeerId>, String>>()?;
+	let ss58_format = config.ss58_format.unwrap_or_else(|| SS58Prefix::get().into());
+	let protocol_id = config.protocol_id.clone();
+
+	Ok(ChainSpec::from_genesis(
+		&config.name,
+		&config.id,
+		chain_type,
+		move || {
+			let genesis = genesis.clone();
+			build_genesis(
+				wasm_binary,
+				genesis.authorities,
+				genesis.root_key,
+				genesis.balances,
+				genesis.claims,
+				genesis.poe_parameters,
+			)
+		},
+		boot_nodes,
+		// Telemetry
 		None,
+		protocol_id.as_deref(),
+		// Fork ID
 		None,
+		Some(properties(&config.token_symbol, config.token_decimals, ss58_format)),
 		// Extensions
 		None,
 	))
 }
 
+/// Decode and check the genesis state of `config`, so that building the genesis block cannot
+/// panic.
+fn genesis_params(config: &NetworkConfig) -> Result<GenesisParams, String> {
+	if config.authorities.is_empty() {
+		return Err("At least one authority is required".into())
+	}
+	let authorities = config
+		.authorities
+		.iter()
+		.map(|keys| {
+			let aura = parse_ss58::<sr25519::Public>("Aura key", &keys.aura)?;
+			let grandpa = parse_ss58::<ed25519::Public>("GRANDPA key", &keys.grandpa)?;
+			Ok((aura.into(), grandpa.into()))
+		})
+		.collect::<Result<Vec<_>, String>>()?;
+
+	let mut funded = BTreeSet::new();
+	let balances = config
+		.endowed_accounts
+		.iter()
+		.map(|endowed| {
+			let account = parse_ss58::<AccountId>("account", &endowed.account)?;
+			let balance = match &endowed.balance {
+				BalanceValue::Number(balance) => Balance::from(*balance),
+				BalanceValue::Text(balance) =>
+					balance.parse().map_err(|_| format!("Invalid balance {}", balance))?,
+			};
+			if balance < EXISTENTIAL_DEPOSIT {
+				return Err(format!("{} is endowed with less than the existential deposit", account))
+			}
+			if !funded.insert(account.clone()) {
+				return Err(format!("{} is endowed twice", account))
+			}
+			Ok((account, balance))
+		})
+		.collect::<Result<Vec<_>, String>>()?;
+
+	if let Some(parameters) = &config.poe_parameters {
+		if !parameters.is_within(&pallet_poe::MaxParameters::<Runtime>::get()) {
+			return Err("PoE parameters exceed the bounds of the runtime".into())
+		}
+	}
+	let max_claim_length = config.poe_parameters.as_ref().map_or_else(
+		|| <Runtime as pallet_poe::Config>::MaxClaimLength::get(),
+		|parameters| parameters.max_claim_length,
+	);
+	let mut claimed = BTreeSet::new();
+	let claims = config
+		.claims
+		.iter()
+		.map(|genesis_claim| {
+			let claim = array_bytes::hex2bytes(&genesis_claim.claim)
+				.map_err(|_| format!("Invalid claim {}", genesis_claim.claim))?;
+			if claim.len() as u32 > max_claim_length {
+				return Err(format!("Claim {} is too long", genesis_claim.claim))
+			}
+			if !<Runtime as pallet_poe::Config>::ClaimValidator::validate(&claim) {
+				return Err(format!("Claim {} is not in a valid format", genesis_claim.claim))
+			}
+			if !claimed.insert(claim.clone()) {
+				return Err(format!("Claim {} is given twice", genesis_claim.claim))
+			}
+			Ok((claim, parse_ss58::<AccountId>("claim owner", &genesis_claim.owner)?))
+		})
+		.collect::<Result<Vec<_>, String>>()?;
+
+	// 创世存证的 NFT 押金由其所有者支付
+	for (_, owner) in &claims {
+		let count = claims.iter().filter(|(_, claim_owner)| claim_owner == owner).count();
+		let required = EXISTENTIAL_DEPOSIT + NftsItemDeposit::get() * count as Balance;
+		let balance = balances
+			.iter()
+			.find(|(account, _)| account == owner)
+			.map_or(0, |(_, balance)| *balance);
+		if balance < required {
+			return Err(format!("{} cannot pay the NFT deposits of its genesis claims", owner))
+		}
+	}
+
+	Ok(GenesisParams {
+		authorities,
+		root_key: parse_ss58("sudo account", &config.sudo)?,
+		balances,
+		claims,
+		poe_parameters: config.poe_parameters.clone(),
+	})
+}
+
+/// Parse an SS58 address of any network.
+fn parse_ss58<T: Ss58Codec>(what: &str, address: &str) -> Result<T, String> {
+	T::from_ss58check_with_version(address)
+		.map(|(public, _)| public)
+		.map_err(|e| format!("Invalid {} {}: {:?}", what, address, e))
+}
+
+/// Fail if any key or account of `genesis` belongs to a development account.
+fn ensure_no_dev_keys(genesis: &GenesisParams) -> Result<(), String> {
+	let seeds = DEV_SEEDS.iter().flat_map(|seed| [seed.to_string(), format!("{}//stash", seed)]);
+	for seed in seeds {
+		let dev_account = get_account_id_from_seed::<sr25519::Public>(&seed);
+		let (dev_aura, dev_grandpa) = authority_keys_from_seed(&seed);
+
+		let uses_dev_account = genesis.root_key == dev_account ||
+			genesis.balances.iter().any(|(account, _)| *account == dev_account) ||
+			genesis.claims.iter().any(|(_, owner)| *owner == dev_account);
+		let uses_dev_keys = genesis
+			.authorities
+			.iter()
+			.any(|(aura, grandpa)| *aura == dev_aura || *grandpa == dev_grandpa);
+		if uses_dev_account || uses_dev_keys {
+			return Err(format!("Live chains must not use the development keys of //{}", seed))
+		}
+	}
+	Ok(())
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
@@ -132,16 +457,39 @@ fn testnet_genesis(
 	endowed_accounts: Vec<AccountId>,
 	_enable_println: bool,
 ) -> RuntimeGenesisConfig {
+	build_genesis(
+		wasm_binary,
+		initial_authorities,
+		root_key,
+		// Configure endowed accounts with initial balance of 1 << 60.
+		endowed_accounts.into_iter().map(|k| (k, 1 << 60)).collect(),
+		Vec::new(),
+		None,
+	)
+}
+
+/// Configure initial storage state for FRAME modules, with the given balances, PoE claims and
+/// parameters.
+fn build_genesis(
+	wasm_binary: &[u8],
+	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	root_key: AccountId,
+	balances: Vec<(AccountId, Balance)>,
+	claims: Vec<(Vec<u8>, AccountId)>,
+	poe_parameters: Option<PoeParameters<BlockNumber>>,
+) -> RuntimeGenesisConfig {
+	// PoE 在创世时创建存证的 NFT 集合，其所有者需要支付集合押金
+	let mut balances = balances;
+	if !balances.iter().any(|(account, _)| *account == PoeNftOwner::get()) {
+		balances.push((PoeNftOwner::get(), EXISTENTIAL_DEPOSIT + NftsCollectionDeposit::get()));
+	}
 	RuntimeGenesisConfig {
 		system: SystemConfig {
 			// Add Wasm runtime to storage.
 			code: wasm_binary.to_vec(),
 			..Default::default()
 		},
-		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
-		},
+		balances: BalancesConfig { balances },
 		aura: AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
 		},
@@ -154,5 +502,7 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		// Without parameters, the PoE pallet starts with the upper bounds of the runtime.
+		poe_module: PoeModuleConfig { parameters: poe_parameters, claims },
 	}
 }
@@ -1,4 +1,12 @@
-use sc_cli::RunCmd;
+use crate::{
+	poe::{export::ExportFormat, hashing::HashAlgorithm},
+	service::Sealing,
+};
+use node_template_runtime::{AccountId, BlockNumber};
+use sc_cli::{
+	BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, RunCmd, SharedParams,
+};
+use std::{io, path::PathBuf};
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +15,14 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Author blocks with manual seal instead of Aura and GRANDPA, for development chains.
+	///
+	/// `instant` seals a block for every transaction, `manual` only on `engine_createBlock` calls
+	/// and `interval=<ms>` every given number of milliseconds. The `engine_createBlock` and
+	/// `engine_finalizeBlock` RPCs are available in every mode.
+	#[arg(long, value_name = "instant|manual|interval=<ms>")]
+	pub sealing: Option<Sealing>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -38,8 +54,7 @@ pub enum Subcommand {
 	Revert(sc_cli::RevertCmd),
 
 	/// Sub-commands concerned with benchmarking.
-	#[command(subcommand)]
-	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+	Benchmark(BenchmarkParams),
 
 	/// Try some command against runtime state.
 	#[cfg(feature = "try-runtime")]
@@ -51,4 +66,332 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Proof of existence tools.
+	#[command(subcommand)]
+	Poe(PoeSubcommand),
+}
+
+/// Proof of existence sub-commands.
+#[derive(Debug, clap::Subcommand)]
+pub enum PoeSubcommand {
+	/// Export a claim from the local database as a W3C Verifiable Credential.
+	ExportCredential(ExportCredentialCmd),
+
+	/// Verify a W3C Verifiable Credential against the local database.
+	VerifyCredential(VerifyCredentialCmd),
+
+	/// Create a claim on a running node.
+	Create(CreateClaimCmd),
+
+	/// Revoke a claim on a running node.
+	Revoke(RevokeClaimCmd),
+
+	/// Transfer a claim to another account on a running node.
+	Transfer(TransferClaimCmd),
+
+	/// Look a claim up in the local database, without starting the node.
+	///
+	/// Exits with 0 if the claim exists, 2 if it is not found and 3 if it was revoked. Revocations
+	/// are only kept for the runtime's `RevocationRetention` blocks, so "not found" means either
+	/// never created or revoked longer ago than that.
+	Verify(VerifyClaimCmd),
+
+	/// Export every claim in the local database as JSON Lines or CSV.
+	Export(ExportClaimsCmd),
+}
+
+/// The benchmarking sub-commands, with the options of the PoE extrinsic builders.
+#[derive(Debug, clap::Args)]
+pub struct BenchmarkParams {
+	#[command(subcommand)]
+	pub cmd: frame_benchmarking_cli::BenchmarkCmd,
+
+	/// Length in bytes of the claims created by the PoE builders of `benchmark extrinsic`, 32 by
+	/// default for the size of a 256 bit hash.
+	#[arg(long, value_name = "BYTES", default_value_t = 32)]
+	pub claim_length: usize,
+}
+
+/// The claim a `poe` command acts on: the hash of a file, or the claim itself.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ClaimParams {
+	/// File whose hash is the claim. It is read in chunks, so it may be larger than memory.
+	#[arg(required_unless_present = "claim", conflicts_with = "claim")]
+	pub file: Option<PathBuf>,
+
+	/// The claim itself, hex encoded, instead of a file.
+	// 写全路径，否则 clap 会把它当成多个参数
+	#[arg(long, value_parser = parse_hex)]
+	pub claim: Option<::std::vec::Vec<u8>>,
+
+	/// Algorithm used to hash the file.
+	#[arg(long, value_enum, default_value_t = HashAlgorithm::Blake2_256)]
+	pub hash: HashAlgorithm,
+}
+
+impl ClaimParams {
+	/// The claim, hashing the file if one was given.
+	pub fn claim(&self) -> io::Result<Vec<u8>> {
+		match (&self.claim, &self.file) {
+			(Some(claim), _) => Ok(claim.clone()),
+			(None, Some(file)) => self.hash.hash_file(file),
+			(None, None) => Err(io::Error::new(io::ErrorKind::InvalidInput, "No claim given")),
+		}
+	}
+}
+
+/// How to sign and submit a transaction to a running node.
+#[derive(Debug, Clone, clap::Args)]
+pub struct SubmitParams {
+	/// WebSocket RPC endpoint of the node to submit to.
+	#[arg(long, default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+
+	/// Secret URI of the sr25519 key to sign with, e.g. `//Alice`.
+	#[arg(long, required_unless_present = "keystore_path", conflicts_with = "keystore_path")]
+	pub suri: Option<String>,
+
+	/// Keystore holding the sr25519 key to sign with.
+	#[arg(long)]
+	pub keystore_path: Option<PathBuf>,
+
+	/// Password of the keystore.
+	#[arg(long, requires = "keystore_path")]
+	pub password: Option<String>,
+
+	/// Key type of the signing key in the keystore.
+	#[arg(long, default_value = "acco", requires = "keystore_path")]
+	pub key_type: String,
+
+	/// Account of the keystore key to sign with. Needed if the keystore holds several keys of
+	/// the key type.
+	#[arg(long, requires = "keystore_path")]
+	pub signer: Option<AccountId>,
+
+	/// When to consider the transaction done.
+	#[arg(long, value_enum, default_value_t = WaitFor::Finalized)]
+	pub wait: WaitFor,
+}
+
+/// Point in a transaction's life a `poe` command waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WaitFor {
+	/// Return once the transaction is included in a block.
+	InBlock,
+	/// Return once the block including the transaction is finalized.
+	Finalized,
+}
+
+/// The `poe create` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct CreateClaimCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub claim: ClaimParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub submit: SubmitParams,
+}
+
+/// The `poe revoke` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct RevokeClaimCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub claim: ClaimParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub submit: SubmitParams,
+}
+
+/// The `poe transfer` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct TransferClaimCmd {
+	/// Account to transfer the claim to, SS58 or hex encoded.
+	#[arg(long)]
+	pub dest: AccountId,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub claim: ClaimParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub submit: SubmitParams,
+}
+
+/// The `poe export-credential` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportCredentialCmd {
+	/// The claim to export, hex encoded.
+	#[arg(value_parser = parse_hex)]
+	pub claim: ::std::vec::Vec<u8>,
+
+	/// Block hash or number to read the claim at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Write the credential to this file instead of stdout.
+	#[arg(long, short)]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The `poe verify-credential` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct VerifyCredentialCmd {
+	/// Path of the credential to verify.
+	pub credential: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The `poe verify` command.
+///
+/// Revocations are only kept for `RevocationRetention` blocks, older ones read as not found.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct VerifyClaimCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub claim: ClaimParams,
+
+	/// Block hash or number to read the claim at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The `poe export` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportClaimsCmd {
+	/// Block hash or number to read the claims at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Format to write the claims in.
+	#[arg(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+	pub format: ExportFormat,
+
+	/// Only export claims owned by this account, SS58 or hex encoded.
+	#[arg(long)]
+	pub owner: Option<AccountId>,
+
+	/// Only export claims created in this block or later.
+	#[arg(long)]
+	pub from: Option<BlockNumber>,
+
+	/// Only export claims created in this block or earlier.
+	#[arg(long)]
+	pub to: Option<BlockNumber>,
+
+	/// Write the claims to this file instead of stdout.
+	#[arg(long, short)]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl CliConfiguration for ExportCredentialCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+impl CliConfiguration for VerifyCredentialCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+impl CliConfiguration for VerifyClaimCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+impl CliConfiguration for ExportClaimsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+	array_bytes::hex2bytes(hex).map_err(|e| format!("Invalid hex: {:?}", e))
 }
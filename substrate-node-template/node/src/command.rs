@@ -1,18 +1,38 @@
 use crate::{
-	benchmarking::{inherent_benchmark_data, RemarkBuilder, TransferKeepAliveBuilder},
+	benchmarking::{
+		inherent_benchmark_data, ClaimOperationBuilder, CreateClaimBuilder, RemarkBuilder,
+		TransferKeepAliveBuilder,
+	},
 	chain_spec,
-	cli::{Cli, Subcommand},
+	cli::{Cli, PoeSubcommand, Subcommand},
+	poe::{self, export::ExportFilter, ClaimStatus},
 	service,
+	service::FullClient,
+	submit,
 };
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
-use node_template_runtime::{Block, EXISTENTIAL_DEPOSIT};
-use sc_cli::SubstrateCli;
+use frame_support::traits::Get;
+use node_template_runtime::{opaque, pallet_poe, Block, Hash, Runtime, EXISTENTIAL_DEPOSIT};
+use sc_cli::{BlockNumberOrHash, SubstrateCli};
 use sc_service::PartialComponents;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+use std::{
+	fs::File,
+	io::{self, BufWriter, Write},
+	path::Path,
+};
 use sp_keyring::Sr25519Keyring;
 
 #[cfg(feature = "try-runtime")]
 use try_runtime_cli::block_building_info::timestamp_with_aura_info;
 
+/// Prefix of `--chain staging:<file>`, a live chain built from a config file.
+const STAGING_PREFIX: &str = "staging:";
+
+/// Prefix of `--chain config:<file>`, a chain of any type built from a config file.
+const CONFIG_PREFIX: &str = "config:";
+
 impl SubstrateCli for Cli {
 	fn impl_name() -> String {
 		"Substrate Node".into()
@@ -42,6 +62,10 @@ impl SubstrateCli for Cli {
 		Ok(match id {
 			"dev" => Box::new(chain_spec::development_config()?),
 			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
+			id if id.starts_with(STAGING_PREFIX) =>
+				Box::new(chain_spec::staging_config(Path::new(&id[STAGING_PREFIX.len()..]))?),
+			id if id.starts_with(CONFIG_PREFIX) =>
+				Box::new(chain_spec::config_file_config(Path::new(&id[CONFIG_PREFIX.len()..]))?),
 			path =>
 				Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
 		})
@@ -104,7 +128,8 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, backend, Some(aux_revert)), task_manager))
 			})
 		},
-		Some(Subcommand::Benchmark(cmd)) => {
+		Some(Subcommand::Benchmark(params)) => {
+			let cmd = &params.cmd;
 			let runner = cli.create_runner(cmd)?;
 
 			runner.sync_run(|config| {
@@ -154,7 +179,7 @@ pub fn run() -> sc_cli::Result<()> {
 					},
 					BenchmarkCmd::Extrinsic(cmd) => {
 						let PartialComponents { client, .. } = service::new_partial(&config)?;
-						// Register the *Remark* and *TKA* builders.
+						// Register the *Remark*, *TKA* and PoE builders.
 						let ext_factory = ExtrinsicFactory(vec![
 							Box::new(RemarkBuilder::new(client.clone())),
 							Box::new(TransferKeepAliveBuilder::new(
@@ -162,6 +187,15 @@ pub fn run() -> sc_cli::Result<()> {
 								Sr25519Keyring::Alice.to_account_id(),
 								EXISTENTIAL_DEPOSIT,
 							)),
+							Box::new(CreateClaimBuilder::new(client.clone(), params.claim_length)?),
+							Box::new(ClaimOperationBuilder::transfer(
+								client.clone(),
+								params.claim_length,
+							)?),
+							Box::new(ClaimOperationBuilder::revoke(
+								client.clone(),
+								params.claim_length,
+							)?),
 						]);
 
 						cmd.run(client, inherent_benchmark_data()?, Vec::new(), &ext_factory)
@@ -202,11 +236,141 @@ pub fn run() -> sc_cli::Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run::<Block>(&config))
 		},
+		Some(Subcommand::Poe(PoeSubcommand::ExportCredential(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				let at = cmd.at.as_ref().map(|at| block_hash(&client, at)).transpose()?;
+				let credential = poe::credential::issue(&*client, &cmd.claim, at)?;
+				let credential =
+					serde_json::to_string_pretty(&credential).map_err(|e| e.to_string())?;
+
+				match &cmd.output {
+					Some(path) => std::fs::write(path, credential)?,
+					None => println!("{}", credential),
+				}
+				Ok(())
+			})
+		},
+		Some(Subcommand::Poe(PoeSubcommand::VerifyCredential(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				let credential = std::fs::read(&cmd.credential)?;
+				let credential = serde_json::from_slice(&credential)
+					.map_err(|e| format!("Invalid credential: {}", e))?;
+
+				let verification = poe::credential::verify(&*client, &credential)?;
+				match verification.reason {
+					None => {
+						println!("Credential is valid");
+						Ok(())
+					},
+					Some(reason) => Err(format!("Credential is not valid: {}", reason).into()),
+				}
+			})
+		},
+		Some(Subcommand::Poe(PoeSubcommand::Create(cmd))) => {
+			let claim = cmd.claim.claim()?;
+			println!("Creating claim {}", array_bytes::bytes2hex("0x", &claim));
+			submit::run(&cmd.submit, pallet_poe::Call::<Runtime>::create_claim { claim }.into())
+		},
+		Some(Subcommand::Poe(PoeSubcommand::Revoke(cmd))) => {
+			let claim = cmd.claim.claim()?;
+			println!("Revoking claim {}", array_bytes::bytes2hex("0x", &claim));
+			submit::run(&cmd.submit, pallet_poe::Call::<Runtime>::revoke_claim { claim }.into())
+		},
+		Some(Subcommand::Poe(PoeSubcommand::Transfer(cmd))) => {
+			let (claim, dest) = (cmd.claim.claim()?, cmd.dest.clone());
+			println!("Transferring claim {} to {}", array_bytes::bytes2hex("0x", &claim), dest);
+			let call = pallet_poe::Call::<Runtime>::transfer_claim { claim, dest };
+			submit::run(&cmd.submit, call.into())
+		},
+		Some(Subcommand::Poe(PoeSubcommand::Verify(cmd))) => {
+			let claim = cmd.claim.claim()?;
+			let runner = cli.create_runner(cmd)?;
+			let mut status = ClaimStatus::NotFound;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				let at = match &cmd.at {
+					Some(at) => block_hash(&client, at)?,
+					None => client.info().best_hash,
+				};
+
+				println!("Claim {}", array_bytes::bytes2hex("0x", &claim));
+				status = poe::claim_status(&*client, at, &claim)?;
+				match &status {
+					ClaimStatus::Found(record) => {
+						let created_hash =
+							client.expect_block_hash_from_id(&BlockId::<opaque::Block>::Number(
+								record.created_at,
+							))?;
+						let created_time = poe::block_timestamp(&*client, created_hash)?;
+						let owner = poe::ss58(&record.owner);
+						match poe::display_name(&*client, at, &record.owner)? {
+							Some(name) => println!("Owner: {} ({})", owner, name),
+							None => println!("Owner: {}", owner),
+						}
+						println!("Block: #{} ({:?})", record.created_at, created_hash);
+						println!("Time:  {}", poe::rfc3339(created_time)?);
+						if let Some(action) = poe::scheduled_action(&*client, at, &claim)? {
+							match action.operation {
+								pallet_poe::ScheduledOperation::Transfer(dest) => println!(
+									"Scheduled: transfer to {} in block #{}",
+									poe::ss58(&dest),
+									action.when
+								),
+								pallet_poe::ScheduledOperation::Revoke =>
+									println!("Scheduled: revocation in block #{}", action.when),
+							}
+						}
+					},
+					ClaimStatus::NotFound => println!(
+						"Not claimed, or revoked more than {} blocks ago",
+						<Runtime as pallet_poe::Config>::RevocationRetention::get()
+					),
+					ClaimStatus::Revoked(revocation) => println!(
+						"Revoked by {} in block #{}",
+						poe::ss58(&revocation.revoked_by),
+						revocation.revoked_at
+					),
+				}
+				Ok::<_, sc_cli::Error>(())
+			})?;
+
+			std::process::exit(status.exit_code())
+		},
+		Some(Subcommand::Poe(PoeSubcommand::Export(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				let at = match &cmd.at {
+					Some(at) => block_hash(&client, at)?,
+					None => client.info().best_hash,
+				};
+				let filter = ExportFilter { owner: cmd.owner.clone(), from: cmd.from, to: cmd.to };
+				let out: Box<dyn Write> = match &cmd.output {
+					Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+					None => Box::new(BufWriter::new(io::stdout())),
+				};
+
+				let exported = poe::export::export(&*client, at, &filter, cmd.format, out)?;
+				eprintln!("Exported {} claims at block {:?}", exported, at);
+				Ok(())
+			})
+		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let sealing = cli.sealing;
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, sealing).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
 }
+
+/// Resolve a block number or hash given on the command line to a block hash.
+fn block_hash(client: &FullClient, at: &BlockNumberOrHash) -> sc_cli::Result<Hash> {
+	let id = at.parse::<opaque::Block>()?;
+	Ok(client.expect_block_hash_from_id(&id)?)
+}
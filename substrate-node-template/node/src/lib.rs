@@ -1,3 +1,4 @@
 pub mod chain_spec;
+pub mod poe;
 pub mod rpc;
 pub mod service;
@@ -7,7 +7,9 @@ mod service;
 mod benchmarking;
 mod cli;
 mod command;
+mod poe;
 mod rpc;
+mod submit;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
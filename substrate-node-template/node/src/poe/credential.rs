@@ -0,0 +1,239 @@
+//! Export of proof of existence claims as W3C Verifiable Credentials.
+//!
+//! The credential's `proof` is a storage read proof of every entry the claim's owner is resolved
+//! from, at the block the credential was issued at, so it can be checked against that block's
+//! state root without trusting the issuing node.
+
+use super::{block_timestamp, read_claim, resolve_claim, rfc3339, ss58};
+use node_template_runtime::{opaque::Block, AccountId, BlockNumber, Hash};
+use sc_client_api::{Backend, BlockBackend, ProofProvider, StorageProvider};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sp_blockchain::HeaderBackend;
+use sp_core::crypto::Ss58Codec;
+use sp_runtime::traits::{BlakeTwo256, Header as HeaderT};
+use sp_trie::{LayoutV1, StorageProof};
+
+/// The credential type of exported claims.
+pub const CREDENTIAL_TYPE: &str = "ProofOfExistenceCredential";
+
+/// The proof type of exported claims.
+pub const PROOF_TYPE: &str = "SubstrateStorageProof";
+
+const CREDENTIALS_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+const POE_VOCABULARY: &str = "https://github.com/wangzhenchuan/substrate-poe#";
+
+/// The outcome of [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialVerification {
+	/// Whether the credential holds.
+	pub valid: bool,
+	/// Why the credential does not hold.
+	pub reason: Option<String>,
+}
+
+/// Why a credential could not be verified.
+enum Failure {
+	/// The credential does not hold.
+	Invalid(String),
+	/// The chain could not be read.
+	Client(String),
+}
+
+impl From<String> for Failure {
+	fn from(reason: String) -> Self {
+		Failure::Invalid(reason)
+	}
+}
+
+impl From<&str> for Failure {
+	fn from(reason: &str) -> Self {
+		Failure::Invalid(reason.into())
+	}
+}
+
+fn client_error(e: impl ToString) -> Failure {
+	Failure::Client(e.to_string())
+}
+
+/// Render `claim` as a Verifiable Credential, reading it at block `at` or at the best block.
+pub fn issue<C, B>(client: &C, claim: &[u8], at: Option<Hash>) -> Result<Value, String>
+where
+	C: HeaderBackend<Block> + BlockBackend<Block>,
+	C: StorageProvider<Block, B> + ProofProvider<Block>,
+	B: Backend<Block>,
+{
+	let at = at.unwrap_or_else(|| client.info().best_hash);
+	let header = client.header(at).map_err(|e| e.to_string())?.ok_or("Block not found")?;
+	// 记下解析所有者时读过的每个存储项，一并证明
+	let mut keys = Vec::new();
+	let record = resolve_claim(claim, |key| {
+		keys.push(key.clone());
+		client
+			.storage(at, key)
+			.map(|data| data.map(|data| data.0))
+			.map_err(|e| e.to_string())
+	})?
+	.ok_or("Claim does not exist")?;
+	let created_hash = client
+		.hash(record.created_at)
+		.map_err(|e| e.to_string())?
+		.ok_or("Claim creation block not found")?;
+	let created_time = block_timestamp(client, created_hash)?;
+
+	let proof = client
+		.read_proof(at, &mut keys.iter().map(|key| key.0.as_slice()))
+		.map_err(|e| e.to_string())?;
+	let chain = chain_did(client.info().genesis_hash);
+
+	Ok(json!({
+		"@context": [CREDENTIALS_CONTEXT, { "@vocab": POE_VOCABULARY }],
+		"type": ["VerifiableCredential", CREDENTIAL_TYPE],
+		"issuer": chain,
+		"issuanceDate": rfc3339(created_time)?,
+		"credentialSubject": {
+			"id": account_did(&record.owner),
+			"claim": to_hex(claim),
+			"blockNumber": record.created_at,
+			"blockHash": to_hex(created_hash),
+			"timestamp": created_time,
+		},
+		"proof": {
+			"type": PROOF_TYPE,
+			"created": rfc3339(block_timestamp(client, at)?)?,
+			"proofPurpose": "assertionMethod",
+			"verificationMethod": chain,
+			"blockNumber": header.number(),
+			"blockHash": to_hex(at),
+			"stateRoot": to_hex(header.state_root()),
+			"storageKeys": keys.iter().map(|key| to_hex(&key.0)).collect::<Vec<_>>(),
+			"proofValue": proof.into_iter_nodes().map(to_hex).collect::<Vec<_>>(),
+		},
+	}))
+}
+
+/// Check a credential produced by [`issue`] against the chain: the proof block must be part of
+/// the chain with the given state root, the proof must show the claim recorded there as
+/// described by the credential, and it must still be held by the same owner at the best block.
+///
+/// Fails only if the chain could not be read; a credential that does not hold is reported in
+/// the returned [`CredentialVerification`].
+pub fn verify<C, B>(client: &C, credential: &Value) -> Result<CredentialVerification, String>
+where
+	C: HeaderBackend<Block> + StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	match check(client, credential) {
+		Ok(()) => Ok(CredentialVerification { valid: true, reason: None }),
+		Err(Failure::Invalid(reason)) =>
+			Ok(CredentialVerification { valid: false, reason: Some(reason) }),
+		Err(Failure::Client(e)) => Err(e),
+	}
+}
+
+fn check<C, B>(client: &C, credential: &Value) -> Result<(), Failure>
+where
+	C: HeaderBackend<Block> + StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	let types = credential["type"].as_array().ok_or("Missing credential type")?;
+	if !types.iter().any(|t| t == CREDENTIAL_TYPE) {
+		return Err(format!("Not a {}", CREDENTIAL_TYPE).into())
+	}
+	if credential["issuer"] != chain_did(client.info().genesis_hash) {
+		return Err("Credential was issued for another chain".into())
+	}
+
+	let subject = &credential["credentialSubject"];
+	let claim = from_hex(field(subject, "claim")?)?;
+	let owner = parse_account_did(field(subject, "id")?)?;
+	let created_at = subject["blockNumber"]
+		.as_u64()
+		.and_then(|n| BlockNumber::try_from(n).ok())
+		.ok_or("Missing claim block number")?;
+	let created_hash = parse_hash(field(subject, "blockHash")?)?;
+
+	let proof = &credential["proof"];
+	if proof["type"] != PROOF_TYPE {
+		return Err(format!("Unsupported proof type, expected {}", PROOF_TYPE).into())
+	}
+	let at = parse_hash(field(proof, "blockHash")?)?;
+	let header = client
+		.header(at)
+		.map_err(client_error)?
+		.ok_or("Proof block is not known to this node")?;
+	if client.hash(*header.number()).map_err(client_error)? != Some(at) {
+		return Err("Proof block is not part of the best chain".into())
+	}
+	if from_hex(field(proof, "stateRoot")?)? != header.state_root().as_bytes() {
+		return Err("State root does not match the proof block".into())
+	}
+	if client.hash(created_at).map_err(client_error)? != Some(created_hash) {
+		return Err("Claim block hash does not match the chain".into())
+	}
+
+	// 所有者只从证明中读取，不读本地状态
+	let nodes = proof["proofValue"]
+		.as_array()
+		.ok_or("Missing proofValue")?
+		.iter()
+		.map(|node| from_hex(node.as_str().ok_or("Invalid proofValue")?))
+		.collect::<Result<Vec<_>, String>>()?;
+	let db = StorageProof::new(nodes).into_memory_db::<BlakeTwo256>();
+	let record = resolve_claim(&claim, |key| {
+		sp_trie::read_trie_value::<LayoutV1<BlakeTwo256>, _>(
+			&db,
+			header.state_root(),
+			&key.0,
+			None,
+			None,
+		)
+		.map_err(|_| "Proof does not cover the claim".to_string())
+	})?
+	.ok_or("Claim does not exist at the proof block")?;
+	if record.owner != owner || record.created_at != created_at {
+		return Err("Claim does not match the proof".into())
+	}
+
+	match read_claim(client, client.info().best_hash, &claim).map_err(Failure::Client)? {
+		Some(current) if current.owner == owner => Ok(()),
+		Some(_) => Err("Claim has since been transferred".into()),
+		None => Err("Claim has since been revoked".into()),
+	}
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Result<&'a str, String> {
+	value[name].as_str().ok_or_else(|| format!("Missing {}", name))
+}
+
+fn chain_did(genesis_hash: Hash) -> String {
+	format!("did:substrate:{}", to_hex(genesis_hash))
+}
+
+fn account_did(account: &AccountId) -> String {
+	format!("did:substrate:{}", ss58(account))
+}
+
+fn parse_account_did(did: &str) -> Result<AccountId, String> {
+	let address = did.strip_prefix("did:substrate:").ok_or("Invalid owner DID")?;
+	AccountId::from_ss58check_with_version(address)
+		.map(|(account, _)| account)
+		.map_err(|e| format!("Invalid owner DID: {:?}", e))
+}
+
+fn parse_hash(hex: &str) -> Result<Hash, String> {
+	let bytes = from_hex(hex)?;
+	if bytes.len() != Hash::len_bytes() {
+		return Err(format!("Invalid block hash {}", hex))
+	}
+	Ok(Hash::from_slice(&bytes))
+}
+
+fn to_hex(bytes: impl AsRef<[u8]>) -> String {
+	array_bytes::bytes2hex("0x", bytes.as_ref())
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+	array_bytes::hex2bytes(hex).map_err(|e| format!("Invalid hex {}: {:?}", hex, e))
+}
@@ -0,0 +1,57 @@
+//! Hashing of files into claims.
+
+use blake2::{digest::consts::U32, Blake2b};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use std::{
+	fs::File,
+	io::{self, Read},
+	path::Path,
+};
+
+/// Size of the chunks files are read in.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Hash algorithm turning a file into a claim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HashAlgorithm {
+	/// BLAKE2b with a 256 bit output, as used by `create_claim_with_content`.
+	#[value(name = "blake2-256")]
+	Blake2_256,
+	/// SHA-256.
+	#[value(name = "sha2-256")]
+	Sha2_256,
+	/// Keccak-256.
+	#[value(name = "keccak-256")]
+	Keccak256,
+}
+
+impl HashAlgorithm {
+	/// Hash the file at `path` without loading it into memory at once.
+	pub fn hash_file(self, path: &Path) -> io::Result<Vec<u8>> {
+		self.hash_reader(File::open(path)?)
+	}
+
+	/// Hash everything `reader` yields.
+	pub fn hash_reader(self, reader: impl Read) -> io::Result<Vec<u8>> {
+		match self {
+			Self::Blake2_256 => digest::<Blake2b<U32>>(reader),
+			Self::Sha2_256 => digest::<Sha256>(reader),
+			Self::Keccak256 => digest::<Keccak256>(reader),
+		}
+	}
+}
+
+fn digest<D: Digest>(mut reader: impl Read) -> io::Result<Vec<u8>> {
+	let mut hasher = D::new();
+	let mut chunk = vec![0; CHUNK_SIZE];
+
+	loop {
+		match reader.read(&mut chunk) {
+			Ok(0) => return Ok(hasher.finalize().to_vec()),
+			Ok(read) => hasher.update(&chunk[..read]),
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+			Err(e) => return Err(e),
+		}
+	}
+}
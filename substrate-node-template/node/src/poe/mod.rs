@@ -0,0 +1,313 @@
+//! Proof of existence tooling shared by the node's CLI and RPC.
+
+pub mod credential;
+pub mod export;
+pub mod hashing;
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use node_template_runtime::{
+	opaque::Block, pallet_poe, AccountId, BlockNumber, Hash, IdentityRegistrars,
+	IdentityRegistration, PoeNftCollection, PoeRegistrar, Runtime, RuntimeCall, SS58Prefix,
+	TimestampCall, UncheckedExtrinsic, VerifiedIdentity,
+};
+use pallet_identity::{Data, IdentityInfo, Judgement, RegistrarInfo};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use sp_core::{
+	crypto::{Ss58AddressFormat, Ss58Codec},
+	hashing::blake2_256,
+	storage::StorageKey,
+	Decode, Encode, Get,
+};
+use sp_runtime::BoundedVec;
+
+/// A claim as recorded in `pallet_poe::Proofs`, with its owner resolved by [`resolve_claim`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimRecord {
+	/// The account owning the claim.
+	pub owner: AccountId,
+	/// The block the claim was created in.
+	pub created_at: BlockNumber,
+}
+
+/// The storage key of `claim` in `pallet_poe::Proofs`.
+pub fn claim_storage_key(claim: &[u8]) -> Result<StorageKey, String> {
+	Ok(StorageKey(pallet_poe::Proofs::<Runtime>::hashed_key_for(bounded(claim)?)))
+}
+
+/// The storage key of `claim` in `pallet_poe::Revocations`.
+pub fn revocation_storage_key(claim: &[u8]) -> Result<StorageKey, String> {
+	Ok(StorageKey(pallet_poe::Revocations::<Runtime>::hashed_key_for(bounded(claim)?)))
+}
+
+fn bounded(
+	claim: &[u8],
+) -> Result<BoundedVec<u8, <Runtime as pallet_poe::Config>::MaxClaimLength>, String> {
+	BoundedVec::try_from(claim.to_vec()).map_err(|_| "Claim is too long".to_string())
+}
+
+/// The revocation of a claim as recorded in `pallet_poe::Revocations`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revocation {
+	/// The account that revoked the claim.
+	pub revoked_by: AccountId,
+	/// The block the claim was revoked in.
+	pub revoked_at: BlockNumber,
+}
+
+/// The state of a claim at some block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimStatus {
+	/// The claim exists.
+	Found(ClaimRecord),
+	/// The claim was never created, or revoked longer than `RevocationRetention` blocks before.
+	NotFound,
+	/// The claim was created and later revoked, within `RevocationRetention` blocks.
+	Revoked(Revocation),
+}
+
+impl ClaimStatus {
+	/// The exit code `poe verify` reports the status with. `1` is left for errors.
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			Self::Found(_) => 0,
+			Self::NotFound => 2,
+			Self::Revoked(_) => 3,
+		}
+	}
+}
+
+/// Read the status of `claim` from the state of block `at`.
+pub fn claim_status<C, B>(client: &C, at: Hash, claim: &[u8]) -> Result<ClaimStatus, String>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	if let Some(record) = read_claim(client, at, claim)? {
+		return Ok(ClaimStatus::Found(record))
+	}
+
+	let key = revocation_storage_key(claim)?;
+	let data = client.storage(at, &key).map_err(|e| e.to_string())?;
+	match data {
+		Some(data) => {
+			let (revoked_by, revoked_at) = <(AccountId, BlockNumber)>::decode(&mut &data.0[..])
+				.map_err(|e| format!("Invalid revocation record: {}", e))?;
+			Ok(ClaimStatus::Revoked(Revocation { revoked_by, revoked_at }))
+		},
+		None => Ok(ClaimStatus::NotFound),
+	}
+}
+
+/// Read `claim` from the state of block `at`.
+pub fn read_claim<C, B>(client: &C, at: Hash, claim: &[u8]) -> Result<Option<ClaimRecord>, String>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	resolve_claim(claim, |key| {
+		client
+			.storage(at, key)
+			.map(|data| data.map(|data| data.0))
+			.map_err(|e| e.to_string())
+	})
+}
+
+/// Read `claim` through `read`, resolving its owner the way `pallet_poe` does: the holder of the
+/// NFT of the claim owns it rather than the `Proofs` owner, which lags behind while the NFT is
+/// transferred on its own, unless the holder fails the identity requirement of the runtime.
+pub fn resolve_claim(
+	claim: &[u8],
+	mut read: impl FnMut(&StorageKey) -> Result<Option<Vec<u8>>, String>,
+) -> Result<Option<ClaimRecord>, String> {
+	let data = match read(&claim_storage_key(claim)?)? {
+		Some(data) => data,
+		None => return Ok(None),
+	};
+	let (owner, created_at) = <(AccountId, BlockNumber)>::decode(&mut &data[..])
+		.map_err(|e| format!("Invalid claim record: {}", e))?;
+
+	let item = blake2_256(claim).into();
+	let key = StorageKey(NftItems::hashed_key_for(PoeNftCollection::get(), item));
+	let holder = read(&key)?
+		// pallet_nfts::ItemDetails 的第一个字段是所有者
+		.map(|data| {
+			AccountId::decode(&mut &data[..]).map_err(|e| format!("Invalid NFT record: {}", e))
+		})
+		.transpose()?;
+
+	let owner = match holder {
+		Some(holder) if holder != owner && is_verified(&holder, &mut read)? => holder,
+		_ => owner,
+	};
+	Ok(Some(ClaimRecord { owner, created_at }))
+}
+
+/// Whether `who` passes the `ClaimantFilter` of the runtime, reading storage through `read`.
+fn is_verified(
+	who: &AccountId,
+	read: &mut impl FnMut(&StorageKey) -> Result<Option<Vec<u8>>, String>,
+) -> Result<bool, String> {
+	let registrars = read(&StorageKey(Registrars::hashed_key().to_vec()))?
+		.map(|data| {
+			IdentityRegistrars::decode(&mut &data[..])
+				.map_err(|e| format!("Invalid registrars: {}", e))
+		})
+		.transpose()?
+		.unwrap_or_default();
+	let registration = read(&StorageKey(IdentityOf::hashed_key_for(who)))?
+		.map(|data| {
+			IdentityRegistration::decode(&mut &data[..])
+				.map_err(|e| format!("Invalid identity: {}", e))
+		})
+		.transpose()?;
+
+	Ok(VerifiedIdentity::is_verified(&registrars, registration.as_ref()))
+}
+
+/// A `transfer_claim` or `revoke_claim` scheduled by the owner of a claim.
+pub type ScheduledAction = pallet_poe::ScheduledAction<AccountId, BlockNumber>;
+
+/// Read the action scheduled for `claim` from the state of block `at`.
+pub fn scheduled_action<C, B>(
+	client: &C,
+	at: Hash,
+	claim: &[u8],
+) -> Result<Option<ScheduledAction>, String>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	let key = StorageKey(pallet_poe::ScheduledActions::<Runtime>::hashed_key_for(bounded(claim)?));
+	let data = client.storage(at, &key).map_err(|e| e.to_string())?;
+
+	data.map(|data| {
+		ScheduledAction::decode(&mut &data.0[..])
+			.map_err(|e| format!("Invalid scheduled action: {}", e))
+	})
+	.transpose()
+}
+
+/// The display name `who` registered with `pallet_identity`, if any.
+pub fn display_name<C, B>(client: &C, at: Hash, who: &AccountId) -> Result<Option<String>, String>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	let key = StorageKey(IdentityOf::hashed_key_for(who));
+	let data = match client.storage(at, &key).map_err(|e| e.to_string())? {
+		Some(data) => data,
+		None => return Ok(None),
+	};
+
+	let registration = IdentityRegistration::decode(&mut &data.0[..])
+		.map_err(|e| format!("Invalid identity: {}", e))?;
+	Ok(match registration.info.display {
+		Data::Raw(name) => Some(String::from_utf8_lossy(&name).into_owned()),
+		_ => None,
+	})
+}
+
+/// Genesis storage entries making `registrar` the `PoeRegistrar` of the runtime, and giving each
+/// of `accounts` an identity it judged `KnownGood`. `pallet_identity` has no genesis config, so
+/// development chains write these into their genesis storage for their accounts to hold claims.
+pub fn verified_identities(
+	registrar: AccountId,
+	accounts: &[AccountId],
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, String> {
+	let index = PoeRegistrar::get();
+	let registrars: Vec<_> = (0..=index)
+		.map(|i| {
+			(i == index).then(|| RegistrarInfo {
+				account: registrar.clone(),
+				fee: 0,
+				fields: Default::default(),
+			})
+		})
+		.collect();
+	let registrars =
+		IdentityRegistrars::try_from(registrars).map_err(|_| "Too many registrars".to_string())?;
+
+	let registration = IdentityRegistration {
+		judgements: vec![(index, Judgement::KnownGood)]
+			.try_into()
+			.map_err(|_| "Too many judgements".to_string())?,
+		deposit: 0,
+		info: IdentityInfo {
+			additional: Default::default(),
+			display: Data::None,
+			legal: Data::None,
+			web: Data::None,
+			riot: Data::None,
+			email: Data::None,
+			pgp_fingerprint: None,
+			image: Data::None,
+			twitter: Data::None,
+		},
+	};
+
+	let mut storage = vec![(Registrars::hashed_key().to_vec(), registrars.encode())];
+	storage.extend(
+		accounts
+			.iter()
+			.map(|account| (IdentityOf::hashed_key_for(account), registration.encode())),
+	);
+	Ok(storage)
+}
+
+// pallet_identity 和 pallet_nfts 的存储项不是公开的，按其定义声明别名，前缀取自运行时
+#[frame_support::storage_alias]
+type IdentityOf = StorageMap<
+	pallet_identity::Pallet<Runtime>,
+	frame_support::Twox64Concat,
+	AccountId,
+	IdentityRegistration,
+>;
+
+#[frame_support::storage_alias]
+type Registrars =
+	StorageValue<pallet_identity::Pallet<Runtime>, IdentityRegistrars, frame_support::ValueQuery>;
+
+// 只用来计算存储键，值按 `ItemDetails` 的前缀解码
+#[frame_support::storage_alias]
+type NftItems = StorageDoubleMap<
+	pallet_nfts::Pallet<Runtime>,
+	frame_support::Blake2_128Concat,
+	u32,
+	frame_support::Blake2_128Concat,
+	Hash,
+	AccountId,
+>;
+
+/// The `pallet_timestamp` time of block `at`, in milliseconds since the unix epoch.
+///
+/// It is taken from the block's timestamp inherent rather than its state, so that it is known
+/// for blocks whose state was pruned.
+pub fn block_timestamp<C>(client: &C, at: Hash) -> Result<u64, String>
+where
+	C: BlockBackend<Block>,
+{
+	let body = client.block_body(at).map_err(|e| e.to_string())?.ok_or("Block not found")?;
+
+	for extrinsic in body {
+		let extrinsic = UncheckedExtrinsic::decode(&mut &extrinsic.encode()[..])
+			.map_err(|e| format!("Invalid extrinsic: {}", e))?;
+		if let RuntimeCall::Timestamp(TimestampCall::set { now }) = extrinsic.function {
+			return Ok(now)
+		}
+	}
+	// 创世区块没有时间戳
+	Ok(0)
+}
+
+/// `account` in the runtime's SS58 address format.
+pub fn ss58(account: &AccountId) -> String {
+	account.to_ss58check_with_version(Ss58AddressFormat::custom(SS58Prefix::get().into()))
+}
+
+/// Format a `pallet_timestamp` time as an RFC 3339 date.
+pub fn rfc3339(millis: u64) -> Result<String, String> {
+	Utc.timestamp_millis_opt(millis as i64)
+		.single()
+		.map(|time| time.to_rfc3339_opts(SecondsFormat::Millis, true))
+		.ok_or_else(|| format!("Invalid timestamp {}", millis))
+}
@@ -0,0 +1,255 @@
+//! RPC methods for the proof of existence pallet.
+
+use std::sync::Arc;
+
+use crate::poe::{
+	self,
+	credential::{self, CredentialVerification},
+};
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::{
+	opaque::{Block, Header},
+	pallet_poe::{AttestationOf, LicenseOf},
+	AccountId, BlockNumber, Hash, PoeModule, Runtime,
+};
+use pallet_poe_runtime_api::PoeApi as PoeRuntimeApi;
+use sc_client_api::{
+	backend::{Backend, OffchainStorage},
+	BlockBackend, ProofProvider, StorageProvider,
+};
+use sc_consensus_grandpa::FinalityProofProvider;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{offchain::STORAGE_PREFIX, Bytes};
+use sp_runtime::traits::Header as HeaderT;
+
+/// Error code of PoE RPC errors.
+const POE_RPC_ERROR: i32 = 1;
+
+/// Proof of the state of a claim at a finalized block.
+///
+/// It can be checked offline against a trusted GRANDPA authority set with the `poe-verifier`
+/// crate, whose `ClaimProof` has the same fields. The set must be the one that finalized the
+/// block, as the proof does not follow authority set changes.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimProof {
+	/// Header of the block the claim was read at.
+	pub header: Header,
+	/// SCALE encoded GRANDPA finality proof for the block.
+	pub finality_proof: Bytes,
+	/// Trie nodes proving the claim's `Proofs` and `Revocations` entries, its NFT and the
+	/// identity of the NFT's holder under the block's state root, everything `poe-verifier`
+	/// reads to resolve the claim's owner.
+	pub storage_proof: Vec<Bytes>,
+}
+
+/// A claim as returned by `poe_getClaim`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimInfo {
+	/// The account owning the claim, the holder of its NFT if the NFT was transferred on its own.
+	pub owner: AccountId,
+	/// The display name the owner registered with `pallet_identity`, if any.
+	pub owner_display_name: Option<String>,
+	/// The block the claim was created in.
+	pub created_at: BlockNumber,
+}
+
+/// PoE RPC methods.
+#[rpc(server)]
+pub trait PoeApi {
+	/// Returns the content stored with `create_claim_with_content` for `claim`, if this node
+	/// indexed it. Requires the node to run with `--enable-offchain-indexing true`.
+	#[method(name = "poe_getContent")]
+	fn content(&self, claim: Bytes) -> RpcResult<Option<Bytes>>;
+
+	/// Returns `claim` with its owner and the owner's display name, read at block `at` or the
+	/// best block, or `null` if the claim does not exist.
+	#[method(name = "poe_getClaim")]
+	fn claim(&self, claim: Bytes, at: Option<Hash>) -> RpcResult<Option<ClaimInfo>>;
+
+	/// Returns `claim` as a W3C Verifiable Credential, read at block `at` or the best block.
+	#[method(name = "poe_getCredential")]
+	fn credential(&self, claim: Bytes, at: Option<Hash>) -> RpcResult<Value>;
+
+	/// Checks a credential returned by `poe_getCredential` against the chain and its proof,
+	/// returning whether it holds and, if not, why.
+	#[method(name = "poe_verifyCredential")]
+	fn verify_credential(&self, credential: Value) -> RpcResult<CredentialVerification>;
+
+	/// Returns a proof of the state of `claim` at the finalized block `at` or the last finalized
+	/// block, to be checked with the GRANDPA authority set that finalized the block.
+	#[method(name = "poe_getClaimProof")]
+	fn claim_proof(&self, claim: Bytes, at: Option<Hash>) -> RpcResult<ClaimProof>;
+
+	/// Returns the attestations of `claim` that have not expired and were made by attesters that
+	/// are still registered, at block `at` or the best block.
+	#[method(name = "poe_getAttestations")]
+	fn attestations(
+		&self,
+		claim: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<AttestationOf<Runtime>>>;
+
+	/// Returns the licences on `claim` that have not expired, at block `at` or the best block.
+	#[method(name = "poe_getLicenses")]
+	fn licenses(&self, claim: Bytes, at: Option<Hash>) -> RpcResult<Vec<LicenseOf<Runtime>>>;
+
+	/// Returns the claims `licensee` holds a licence on that has not expired, with the licence,
+	/// at block `at` or the best block.
+	#[method(name = "poe_getLicensesOf")]
+	fn licenses_of(
+		&self,
+		licensee: AccountId,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<(Bytes, LicenseOf<Runtime>)>>;
+}
+
+/// Provides RPC methods to query the proof of existence pallet.
+pub struct Poe<C, B> {
+	client: Arc<C>,
+	backend: Arc<B>,
+	finality_provider: Arc<FinalityProofProvider<B, Block>>,
+}
+
+impl<C, B> Poe<C, B> {
+	/// Creates a new instance of the PoE RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<B>,
+		finality_provider: Arc<FinalityProofProvider<B, Block>>,
+	) -> Self {
+		Self { client, backend, finality_provider }
+	}
+}
+
+impl<C, B> PoeApiServer for Poe<C, B>
+where
+	C: HeaderBackend<Block> + BlockBackend<Block>,
+	C: StorageProvider<Block, B> + ProofProvider<Block>,
+	C: ProvideRuntimeApi<Block>,
+	C: Send + Sync + 'static,
+	C::Api: PoeRuntimeApi<Block, AccountId, AttestationOf<Runtime>, LicenseOf<Runtime>>,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	fn content(&self, claim: Bytes) -> RpcResult<Option<Bytes>> {
+		let content = self
+			.backend
+			.offchain_storage()
+			.and_then(|storage| storage.get(STORAGE_PREFIX, &PoeModule::content_key(&claim)));
+
+		Ok(content.map(Into::into))
+	}
+
+	fn claim(&self, claim: Bytes, at: Option<Hash>) -> RpcResult<Option<ClaimInfo>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let record = match poe::read_claim(&*self.client, at, &claim).map_err(rpc_error)? {
+			Some(record) => record,
+			None => return Ok(None),
+		};
+		let owner_display_name =
+			poe::display_name(&*self.client, at, &record.owner).map_err(rpc_error)?;
+
+		Ok(Some(ClaimInfo {
+			owner: record.owner,
+			owner_display_name,
+			created_at: record.created_at,
+		}))
+	}
+
+	fn credential(&self, claim: Bytes, at: Option<Hash>) -> RpcResult<Value> {
+		credential::issue(&*self.client, &claim, at).map_err(rpc_error)
+	}
+
+	fn verify_credential(&self, credential: Value) -> RpcResult<CredentialVerification> {
+		credential::verify(&*self.client, &credential).map_err(rpc_error)
+	}
+
+	fn claim_proof(&self, claim: Bytes, at: Option<Hash>) -> RpcResult<ClaimProof> {
+		let info = self.client.info();
+		let at = at.unwrap_or(info.finalized_hash);
+		let header = self
+			.client
+			.header(at)
+			.map_err(|e| rpc_error(e.to_string()))?
+			.ok_or_else(|| rpc_error(format!("Unknown block {:?}", at)))?;
+		let number = *header.number();
+		if number > info.finalized_number ||
+			self.client.hash(number).map_err(|e| rpc_error(e.to_string()))? != Some(at)
+		{
+			return Err(rpc_error(format!("Block {:?} is not finalized", at)))
+		}
+
+		// 证明解析所有者时读取的每个键，以及存证的撤销记录
+		let mut keys = vec![poe::revocation_storage_key(&claim).map_err(rpc_error)?];
+		poe::resolve_claim(&claim, |key| {
+			keys.push(key.clone());
+			self.client
+				.storage(at, key)
+				.map(|data| data.map(|data| data.0))
+				.map_err(|e| e.to_string())
+		})
+		.map_err(rpc_error)?;
+		let storage_proof = self
+			.client
+			.read_proof(at, &mut keys.iter().map(|key| key.0.as_slice()))
+			.map_err(|e| rpc_error(e.to_string()))?;
+		let finality_proof = self
+			.finality_provider
+			.prove_finality(number)
+			.map_err(|e| rpc_error(e.to_string()))?
+			.ok_or_else(|| rpc_error(format!("No finality proof for block {:?}", at)))?;
+
+		Ok(ClaimProof {
+			header,
+			finality_proof: finality_proof.into(),
+			storage_proof: storage_proof.into_iter_nodes().map(Into::into).collect(),
+		})
+	}
+
+	fn attestations(
+		&self,
+		claim: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<AttestationOf<Runtime>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.attestations(at, claim.to_vec())
+			.map_err(|e| rpc_error(e.to_string()))
+	}
+
+	fn licenses(&self, claim: Bytes, at: Option<Hash>) -> RpcResult<Vec<LicenseOf<Runtime>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.licenses(at, claim.to_vec())
+			.map_err(|e| rpc_error(e.to_string()))
+	}
+
+	fn licenses_of(
+		&self,
+		licensee: AccountId,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<(Bytes, LicenseOf<Runtime>)>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let licenses = self
+			.client
+			.runtime_api()
+			.licenses_of(at, licensee)
+			.map_err(|e| rpc_error(e.to_string()))?;
+
+		Ok(licenses.into_iter().map(|(claim, license)| (claim.into(), license)).collect())
+	}
+}
+
+fn rpc_error(message: String) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(POE_RPC_ERROR, message, None::<()>)).into()
+}
@@ -1,16 +1,30 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use futures::FutureExt;
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
+use futures::{channel::mpsc, stream, FutureExt, Stream, StreamExt};
+use futures_timer::Delay;
+use node_template_runtime::{self, opaque::Block, Hash, RuntimeApi};
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
+use sc_consensus_manual_seal::{
+	consensus::aura::AuraConsensusDataProvider, EngineCommand, ManualSealParams,
+};
 pub use sc_executor::NativeElseWasmExecutor;
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncParams};
+use sc_service::{
+	error::Error as ServiceError, ChainType, Configuration, TaskManager, WarpSyncParams,
+};
 use sc_telemetry::{Telemetry, TelemetryWorker};
-use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sc_transaction_pool_api::{OffchainTransactionPoolFactory, TransactionPool};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use std::{
+	pin::Pin,
+	str::FromStr,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -36,6 +50,35 @@ pub(crate) type FullClient =
 	sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
+type FullPool = sc_transaction_pool::FullPool<Block, FullClient>;
+
+/// How a development node authors blocks instead of Aura and GRANDPA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal and finalize a block as soon as a transaction enters the pool.
+	Instant,
+	/// Only seal blocks on `engine_createBlock` calls.
+	Manual,
+	/// Seal and finalize a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Self::Instant),
+			"manual" => Ok(Self::Manual),
+			_ => s
+				.strip_prefix("interval=")
+				.and_then(|millis| millis.parse().ok())
+				.filter(|millis| *millis > 0)
+				.map(Self::Interval)
+				.ok_or_else(|| format!("Expected instant, manual or interval=<ms>, got {}", s)),
+		}
+	}
+}
 
 #[allow(clippy::type_complexity)]
 pub fn new_partial(
@@ -46,7 +89,35 @@ pub fn new_partial(
 		FullBackend,
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block, FullClient>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		FullPool,
+		(
+			sc_consensus_grandpa::GrandpaBlockImport<
+				FullBackend,
+				Block,
+				FullClient,
+				FullSelectChain,
+			>,
+			sc_consensus_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
+			Option<Telemetry>,
+		),
+	>,
+	ServiceError,
+> {
+	new_partial_with_sealing(config, None)
+}
+
+/// Like [`new_partial`], importing blocks without Aura checks if the node uses `sealing`.
+#[allow(clippy::type_complexity)]
+fn new_partial_with_sealing(
+	config: &Configuration,
+	sealing: Option<Sealing>,
+) -> Result<
+	sc_service::PartialComponents<
+		FullClient,
+		FullBackend,
+		FullSelectChain,
+		sc_consensus::DefaultImportQueue<Block, FullClient>,
+		FullPool,
 		(
 			sc_consensus_grandpa::GrandpaBlockImport<
 				FullBackend,
@@ -102,6 +173,26 @@ pub fn new_partial(
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
 
+	// 手动出块的区块没有 Aura 签名，不能走 Aura 的导入检查
+	if sealing.is_some() {
+		let import_queue = sc_consensus_manual_seal::import_queue(
+			Box::new(client.clone()),
+			&task_manager.spawn_essential_handle(),
+			config.prometheus_registry(),
+		);
+
+		return Ok(sc_service::PartialComponents {
+			client,
+			backend,
+			task_manager,
+			import_queue,
+			keystore_container,
+			select_chain,
+			transaction_pool,
+			other: (grandpa_block_import, grandpa_link, telemetry),
+		})
+	}
+
 	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
 	let import_queue =
@@ -140,7 +231,16 @@ pub fn new_partial(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// With `sealing`, blocks are authored by `sc-consensus-manual-seal` and no GRANDPA voter runs.
+pub fn new_full(
+	config: Configuration,
+	sealing: Option<Sealing>,
+) -> Result<TaskManager, ServiceError> {
+	if sealing.is_some() && config.chain_spec.chain_type() == ChainType::Live {
+		return Err(ServiceError::Other("--sealing cannot be used on live chains".into()))
+	}
+
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -150,7 +250,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		select_chain,
 		transaction_pool,
 		other: (block_import, grandpa_link, mut telemetry),
-	} = new_partial(&config)?;
+	} = new_partial_with_sealing(&config, sealing)?;
 
 	let mut net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
 
@@ -208,13 +308,33 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	// engine_createBlock / engine_finalizeBlock 的命令通道
+	let (command_sink, rpc_commands) = match sealing {
+		Some(_) => {
+			let (sink, commands) = mpsc::channel(1024);
+			(Some(sink), Some(commands))
+		},
+		None => (None, None),
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let backend = backend.clone();
+		let finality_provider = sc_consensus_grandpa::FinalityProofProvider::new_for_service(
+			backend.clone(),
+			Some(grandpa_link.shared_authority_set().clone()),
+		);
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				backend: backend.clone(),
+				finality_provider: finality_provider.clone(),
+				command_sink: command_sink.clone(),
+				deny_unsafe,
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -234,6 +354,22 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		telemetry: telemetry.as_mut(),
 	})?;
 
+	if let (Some(sealing), Some(rpc_commands)) = (sealing, rpc_commands) {
+		start_manual_seal(
+			sealing,
+			&task_manager,
+			client,
+			select_chain,
+			transaction_pool,
+			rpc_commands,
+			prometheus_registry.as_ref(),
+			telemetry.as_ref(),
+		)?;
+
+		network_starter.start_network();
+		return Ok(task_manager)
+	}
+
 	if role.is_authority() {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -329,3 +465,85 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	network_starter.start_network();
 	Ok(task_manager)
 }
+
+/// Author blocks with `sc-consensus-manual-seal` on the commands of the `engine_*` RPCs and, unless
+/// `sealing` is manual, whenever the pool receives a transaction or the interval elapses.
+#[allow(clippy::too_many_arguments)]
+fn start_manual_seal(
+	sealing: Sealing,
+	task_manager: &TaskManager,
+	client: Arc<FullClient>,
+	select_chain: FullSelectChain,
+	transaction_pool: Arc<FullPool>,
+	rpc_commands: mpsc::Receiver<EngineCommand<Hash>>,
+	prometheus_registry: Option<&substrate_prometheus_endpoint::Registry>,
+	telemetry: Option<&Telemetry>,
+) -> Result<(), ServiceError> {
+	let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry,
+		telemetry.map(|x| x.handle()),
+	);
+
+	let seal = |create_empty| EngineCommand::SealNewBlock {
+		create_empty,
+		finalize: true,
+		parent_hash: None,
+		sender: None,
+	};
+	let commands_stream: Pin<Box<dyn Stream<Item = EngineCommand<Hash>> + Send>> = match sealing {
+		Sealing::Manual => Box::pin(rpc_commands),
+		Sealing::Instant => Box::pin(stream::select(
+			rpc_commands,
+			transaction_pool.import_notification_stream().map(move |_| seal(false)),
+		)),
+		Sealing::Interval(millis) => Box::pin(stream::select(
+			rpc_commands,
+			stream::unfold((), move |()| async move {
+				Delay::new(Duration::from_millis(millis)).await;
+				Some((seal(true), ()))
+			}),
+		)),
+	};
+
+	// The runtime still runs Aura, which requires the slot to increase with every block. Blocks
+	// sealed in quick succession are thus timestamped one slot after their parent, ahead of the
+	// wall clock.
+	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+	let best_timestamp = crate::poe::block_timestamp(&*client, client.chain_info().best_hash)
+		.map_err(ServiceError::Other)?;
+	let last_timestamp = Arc::new(AtomicU64::new(best_timestamp));
+	let create_inherent_data_providers = move |_, ()| {
+		let last_timestamp = last_timestamp.clone();
+		async move {
+			let next_slot = last_timestamp.load(Ordering::SeqCst) + slot_duration.as_millis();
+			let timestamp = sp_timestamp::Timestamp::current().as_millis().max(next_slot);
+			last_timestamp.store(timestamp, Ordering::SeqCst);
+
+			Ok(sp_timestamp::InherentDataProvider::new(timestamp.into()))
+		}
+	};
+
+	let authorship = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+		block_import: client.clone(),
+		env: proposer_factory,
+		client: client.clone(),
+		pool: transaction_pool,
+		commands_stream,
+		select_chain,
+		consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client))),
+		create_inherent_data_providers,
+	});
+
+	// the sealing task is considered essential, i.e. if it
+	// fails we take down the service with it.
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"manual-seal",
+		Some("block-authoring"),
+		authorship,
+	);
+
+	Ok(())
+}
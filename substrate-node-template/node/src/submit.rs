@@ -0,0 +1,225 @@
+//! Signing and submission of proof of existence transactions to a running node.
+
+use crate::{
+	benchmarking::{signed_payload, SigningContext},
+	cli::{SubmitParams, WaitFor},
+};
+use frame_support::traits::PalletInfoAccess;
+use futures::StreamExt;
+use jsonrpsee::{
+	core::{
+		client::{ClientT, Subscription, SubscriptionClientT},
+		params::ArrayParams,
+	},
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use node_template_runtime::{
+	self as runtime, opaque, pallet_poe, AccountId, Hash, Nonce, Runtime, RuntimeCall,
+	RuntimeEvent,
+};
+use sc_keystore::LocalKeystore;
+use sc_transaction_pool_api::TransactionStatus;
+use serde::{de::DeserializeOwned, Deserialize};
+use sp_core::{
+	crypto::{KeyTypeId, SecretString, Ss58Codec},
+	hashing::{blake2_256, twox_128},
+	sr25519,
+	storage::StorageKey,
+	Bytes, Decode, Encode, Pair,
+};
+use sp_keystore::Keystore;
+use sp_runtime::{generic::SignedBlock, traits::Header as _, DispatchError, ModuleError};
+
+/// Key signing submitted transactions.
+enum Signer {
+	/// A key given on the command line.
+	Pair(sr25519::Pair),
+	/// A key held by a keystore.
+	Keystore { keystore: LocalKeystore, key_type: KeyTypeId, public: sr25519::Public },
+}
+
+impl Signer {
+	fn new(params: &SubmitParams) -> Result<Self, String> {
+		if let Some(suri) = &params.suri {
+			let pair = sr25519::Pair::from_string(suri, None)
+				.map_err(|e| format!("Invalid secret URI: {:?}", e))?;
+			return Ok(Self::Pair(pair))
+		}
+
+		let path = params.keystore_path.as_ref().ok_or("Either --suri or --keystore-path")?;
+		let password = params.password.clone().map(SecretString::new);
+		let keystore = LocalKeystore::open(path, password).map_err(|e| e.to_string())?;
+		let key_type = KeyTypeId::try_from(params.key_type.as_str())
+			.map_err(|_| format!("Invalid key type {}", params.key_type))?;
+
+		let keys = keystore.sr25519_public_keys(key_type);
+		let public = match &params.signer {
+			Some(signer) =>
+				keys.into_iter().find(|key| AccountId::from(*key) == *signer).ok_or_else(|| {
+					format!("No {} key for {} in the keystore", params.key_type, signer)
+				})?,
+			None => match keys[..] {
+				[key] => key,
+				[] => return Err(format!("No {} key in the keystore", params.key_type)),
+				_ => return Err("Several keys in the keystore, choose one with --signer".into()),
+			},
+		};
+
+		Ok(Self::Keystore { keystore, key_type, public })
+	}
+
+	fn public(&self) -> sr25519::Public {
+		match self {
+			Self::Pair(pair) => pair.public(),
+			Self::Keystore { public, .. } => *public,
+		}
+	}
+
+	fn sign(&self, message: &[u8]) -> Result<sr25519::Signature, String> {
+		match self {
+			Self::Pair(pair) => Ok(pair.sign(message)),
+			Self::Keystore { keystore, key_type, public } => keystore
+				.sr25519_sign(*key_type, public, message)
+				.map_err(|e| e.to_string())?
+				.ok_or_else(|| "The key disappeared from the keystore".into()),
+		}
+	}
+}
+
+/// The part of `state_getRuntimeVersion` a transaction commits to.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RuntimeVersion {
+	spec_version: u32,
+	transaction_version: u32,
+}
+
+/// Sign `call` and submit it to the node at `params.url`, blocking until `params.wait`.
+pub fn run(params: &SubmitParams, call: RuntimeCall) -> sc_cli::Result<()> {
+	let runtime = sc_cli::build_runtime()?;
+	runtime.block_on(submit(params, call)).map_err(Into::into)
+}
+
+async fn submit(params: &SubmitParams, call: RuntimeCall) -> Result<(), String> {
+	let signer = Signer::new(params)?;
+	let account = AccountId::from(signer.public());
+	let client = WsClientBuilder::default()
+		.build(&params.url)
+		.await
+		.map_err(|e| format!("Cannot connect to {}: {}", params.url, e))?;
+
+	let genesis_hash: Hash = request(&client, "chain_getBlockHash", rpc_params![0u32]).await?;
+	let best: opaque::Header = request(&client, "chain_getHeader", rpc_params![]).await?;
+	let version: RuntimeVersion =
+		request(&client, "state_getRuntimeVersion", rpc_params![]).await?;
+	let nonce: Nonce =
+		request(&client, "system_accountNextIndex", rpc_params![account.to_ss58check()]).await?;
+
+	let context = SigningContext {
+		genesis_hash,
+		best_hash: best.hash(),
+		best_number: best.number,
+		spec_version: version.spec_version,
+		transaction_version: version.transaction_version,
+	};
+	let payload = signed_payload(&context, call, nonce);
+	let signature = payload.using_encoded(|payload| signer.sign(payload))?;
+	let (call, extra, _) = payload.deconstruct();
+	let extrinsic = runtime::UncheckedExtrinsic::new_signed(
+		call,
+		account.into(),
+		runtime::Signature::Sr25519(signature),
+		extra,
+	)
+	.encode();
+
+	let mut status: Subscription<TransactionStatus<Hash, Hash>> = client
+		.subscribe(
+			"author_submitAndWatchExtrinsic",
+			rpc_params![Bytes(extrinsic.clone())],
+			"author_unwatchExtrinsic",
+		)
+		.await
+		.map_err(|e| format!("Cannot submit the transaction: {}", e))?;
+	println!("Submitted transaction {:?}", Hash::from(blake2_256(&extrinsic)));
+
+	while let Some(next) = status.next().await {
+		match next.map_err(|e| e.to_string())? {
+			TransactionStatus::Future |
+			TransactionStatus::Ready |
+			TransactionStatus::Broadcast(_) => {},
+			TransactionStatus::InBlock(block) => {
+				println!("Included in block {:?}", block);
+				ensure_dispatched(&client, block, &extrinsic).await?;
+				if params.wait == WaitFor::InBlock {
+					return Ok(())
+				}
+			},
+			TransactionStatus::Retracted(block) => println!("Block {:?} was retracted", block),
+			TransactionStatus::Finalized(block) => {
+				println!("Finalized in block {:?}", block);
+				return Ok(())
+			},
+			status => return Err(format!("Transaction did not make it: {:?}", status)),
+		}
+	}
+
+	Err("The node closed the subscription".into())
+}
+
+/// Fail if `extrinsic` did not dispatch successfully in `block`.
+async fn ensure_dispatched(client: &WsClient, block: Hash, extrinsic: &[u8]) -> Result<(), String> {
+	let signed: SignedBlock<opaque::Block> =
+		request(client, "chain_getBlock", rpc_params![block]).await?;
+	let index = signed
+		.block
+		.extrinsics
+		.iter()
+		.position(|xt| xt.encode() == extrinsic)
+		.ok_or("The transaction is not in the block")?;
+
+	// frame_system::Events 不是公开的存储项，这里直接拼出存储键
+	let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+	let events: Option<Bytes> =
+		request(client, "state_getStorage", rpc_params![key, block]).await?;
+	let events = match events {
+		Some(events) =>
+			Vec::<frame_system::EventRecord<RuntimeEvent, Hash>>::decode(&mut &events[..])
+				.map_err(|e| format!("Invalid events: {}", e))?,
+		None => Vec::new(),
+	};
+
+	for record in events {
+		if record.phase != frame_system::Phase::ApplyExtrinsic(index as u32) {
+			continue
+		}
+		if let RuntimeEvent::System(frame_system::Event::ExtrinsicFailed {
+			dispatch_error, ..
+		}) = record.event
+		{
+			return Err(format!("Transaction failed: {}", describe(dispatch_error)))
+		}
+	}
+	Ok(())
+}
+
+/// Name PoE errors instead of printing their module and error indices.
+fn describe(error: DispatchError) -> String {
+	if let DispatchError::Module(ModuleError { index, error: code, .. }) = error {
+		if index as usize == runtime::PoeModule::index() {
+			if let Ok(error) = pallet_poe::Error::<Runtime>::decode(&mut &code[..]) {
+				return format!("{:?}", error)
+			}
+		}
+	}
+	format!("{:?}", error)
+}
+
+async fn request<R: DeserializeOwned>(
+	client: &WsClient,
+	method: &str,
+	params: ArrayParams,
+) -> Result<R, String> {
+	client.request(method, params).await.map_err(|e| format!("{} failed: {}", method, e))
+}
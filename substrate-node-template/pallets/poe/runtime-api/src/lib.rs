@@ -0,0 +1,26 @@
+//! Runtime API of the proof of existence pallet, for RPCs to query claims at a block.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Queries of the proof of existence pallet.
+	pub trait PoeApi<AccountId, Attestation, License>
+	where
+		AccountId: Codec,
+		Attestation: Codec,
+		License: Codec,
+	{
+		/// The attestations of `claim` that have not expired and were made by attesters that are
+		/// still registered.
+		fn attestations(claim: Vec<u8>) -> Vec<Attestation>;
+
+		/// The licences on `claim` that have not expired.
+		fn licenses(claim: Vec<u8>) -> Vec<License>;
+
+		/// The claims `licensee` holds a licence on that has not expired, with the licence.
+		fn licenses_of(licensee: AccountId) -> Vec<(Vec<u8>, License)>;
+	}
+}
@@ -0,0 +1,216 @@
+//! Transaction pool validation for `pallet_poe` calls.
+
+use crate::{Call, Commitments, Config, Error, Pallet, Proofs, RevocationExpiries};
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::DispatchInfo,
+	traits::{Contains, Get, IsSubType, ReservableCurrency},
+	BoundedVec,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{DispatchInfoOf, Dispatchable, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	Saturating,
+};
+use sp_std::{fmt, marker::PhantomData};
+
+/// Tag prefix used for the `provides` tags of `pallet_poe` transactions.
+const TAG_PREFIX: &str = "PoeModule";
+
+/// Validates `pallet_poe` calls before they enter the transaction pool.
+///
+/// Claims that are too long, already exist or are not owned by the sender are rejected without
+/// paying fees, and only one pending creation per claim is kept in the pool.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ValidateClaim<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> ValidateClaim<T> {
+	/// Create new `SignedExtension` to validate claims.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for ValidateClaim<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> fmt::Debug for ValidateClaim<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ValidateClaim")
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for ValidateClaim<T>
+where
+	<T as frame_system::Config>::RuntimeCall:
+		Dispatchable<Info = DispatchInfo> + IsSubType<Call<T>>,
+{
+	const IDENTIFIER: &'static str = "ValidateClaim";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		match call.is_sub_type() {
+			Some(call) => Pallet::<T>::validate_claim_call(who, call),
+			None => Ok(ValidTransaction::default()),
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		self.validate(who, call, info, len).map(|_| ())
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Check `call` against the current state as the dispatchable would.
+	pub fn validate_claim_call(who: &T::AccountId, call: &Call<T>) -> TransactionValidity {
+		match call {
+			Call::create_claim_with_content { content } => {
+				ensure_valid(
+					content.len() as u32 <= Self::parameters().max_content_length,
+					Error::<T>::ContentTooLong,
+				)?;
+				let claim = sp_io::hashing::blake2_256(content);
+				Self::validate_claim_creation(who, &claim)
+			},
+			Call::create_claim { claim } => Self::validate_claim_creation(who, claim),
+			Call::revoke_claim { claim } | Call::transfer_claim { claim, .. } => {
+				let bounded_claim = Self::bounded_claim(claim)?;
+				let (owner, _) = Self::current_owner(&bounded_claim)
+					.ok_or(invalid(Error::<T>::ClaimNotExist))?;
+				ensure_valid(
+					Self::is_approved_or_owner(&bounded_claim, &owner, who),
+					Error::<T>::NotClaimOwner,
+				)?;
+				if let Call::transfer_claim { dest, .. } = call {
+					ensure_valid(
+						T::ClaimantFilter::contains(who) && T::ClaimantFilter::contains(dest),
+						Error::<T>::IdentityRequired,
+					)?;
+				} else {
+					Self::validate_revocation_limit()?;
+				}
+
+				Ok(ValidTransaction::default())
+			},
+			Call::commit_claim { commitment } => {
+				ensure_valid(
+					!Commitments::<T>::contains_key(commitment),
+					Error::<T>::CommitmentAlreadyExist,
+				)?;
+				if !T::Currency::can_reserve(who, T::CommitDeposit::get()) {
+					return Err(InvalidTransaction::Payment.into())
+				}
+
+				ValidTransaction::with_tag_prefix(TAG_PREFIX).and_provides(commitment).build()
+			},
+			Call::reveal_claim { claim, salt } => {
+				let bounded_claim = Self::new_claim(claim.clone()).map_err(invalid::<T>)?;
+				let commitment = Self::commitment_of(who, claim, salt);
+				let (_, committed_at, _) = Commitments::<T>::get(commitment)
+					.ok_or(invalid(Error::<T>::CommitmentNotExist))?;
+				let now = frame_system::Pallet::<T>::block_number();
+				ensure_valid(
+					now <= committed_at.saturating_add(Self::parameters().reveal_window),
+					Error::<T>::RevealWindowExpired,
+				)?;
+				if let Some((_, created_at)) = Proofs::<T>::get(&bounded_claim) {
+					ensure_valid(created_at > committed_at, Error::<T>::ProofAlreadyExist)?;
+				}
+				ensure_valid(T::ClaimantFilter::contains(who), Error::<T>::IdentityRequired)?;
+				Self::validate_rate_limit(who)?;
+
+				// A reveal must not compete with `create_claim` for the claim's tag, otherwise a
+				// front-runner could evict it from the pool with a higher tip.
+				ValidTransaction::with_tag_prefix(TAG_PREFIX)
+					.and_provides((b"reveal", commitment))
+					.build()
+			},
+			_ => Ok(ValidTransaction::default()),
+		}
+	}
+
+	fn validate_claim_creation(who: &T::AccountId, claim: &[u8]) -> TransactionValidity {
+		let bounded_claim = Self::new_claim(claim.to_vec()).map_err(invalid::<T>)?;
+		ensure_valid(!Proofs::<T>::contains_key(&bounded_claim), Error::<T>::ProofAlreadyExist)?;
+		ensure_valid(T::ClaimantFilter::contains(who), Error::<T>::IdentityRequired)?;
+		Self::validate_rate_limit(who)?;
+
+		ValidTransaction::with_tag_prefix(TAG_PREFIX).and_provides(bounded_claim).build()
+	}
+
+	/// Check the rate limits for a claim created by `who`.
+	///
+	/// A block that already holds `max_claims_per_block` claims is reported as exhausted resources,
+	/// like a full block, so block authors keep the transaction for the next block instead of
+	/// dropping it.
+	fn validate_rate_limit(who: &T::AccountId) -> Result<(), TransactionValidityError> {
+		let now = frame_system::Pallet::<T>::block_number();
+		if Self::claims_in_block(now) >= Self::parameters().max_claims_per_block {
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+
+		Self::ensure_within_rate_limit(who).map_err(invalid::<T>)
+	}
+
+	/// Check that the block has room left for a revocation. A full block is reported as
+	/// exhausted resources, as in [`Self::validate_rate_limit`].
+	fn validate_revocation_limit() -> Result<(), TransactionValidityError> {
+		let now = frame_system::Pallet::<T>::block_number();
+		let expires_at = now.saturating_add(T::RevocationRetention::get());
+		if RevocationExpiries::<T>::decode_len(expires_at).unwrap_or(0) as u32 >=
+			T::MaxRevocationsPerBlock::get()
+		{
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+
+		Ok(())
+	}
+
+	fn bounded_claim(
+		claim: &[u8],
+	) -> Result<BoundedVec<u8, T::MaxClaimLength>, TransactionValidityError> {
+		BoundedVec::try_from(claim.to_vec()).map_err(|_| invalid(Error::<T>::ClaimTooLong))
+	}
+}
+
+/// Turn a pallet error into an invalid transaction, using the error's index as the custom code.
+fn invalid<T: Config>(error: Error<T>) -> TransactionValidityError {
+	InvalidTransaction::Custom(error.encode()[0]).into()
+}
+
+fn ensure_valid<T: Config>(
+	condition: bool,
+	error: Error<T>,
+) -> Result<(), TransactionValidityError> {
+	if condition {
+		Ok(())
+	} else {
+		Err(invalid(error))
+	}
+}
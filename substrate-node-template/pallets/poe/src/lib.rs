@@ -3,25 +3,194 @@
 /// A module for proof of existence
 pub use pallet::*;
 
+mod extension;
+pub use extension::ValidateClaim;
+
+mod nft;
+pub use nft::{ClaimNfts, NftsAdapter};
+
+mod offchain;
+pub use offchain::ENDPOINT_KEY;
+
+mod traits;
+pub use traits::{ClaimInspect, ClaimMutate, OnClaimCreated, OnClaimRevoked, OnClaimTransferred};
+
+mod validator;
+pub use validator::{ClaimValidator, ExactLength, ForbiddenPrefixes, MinLength, Multihash};
+
+pub mod types;
+pub use types::{Attestation, License, PoeParameters, ScheduledAction, ScheduledOperation};
+
+use sp_core::crypto::KeyTypeId;
+
+/// Key type of the keys the off-chain worker signs availability attestations with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"poe!");
+
+/// Application crypto used by the off-chain worker to sign availability attestations.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_core::sr25519::Signature as Sr25519Signature;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		traits::Verify,
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct AuthorityId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for AuthorityId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+
+	impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature>
+		for AuthorityId
+	{
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::{
         dispatch::DispatchResultWithPostInfo, 
         pallet_prelude::*
     };
-	use frame_system::pallet_prelude::*;
-    use sp_std::vec::Vec;
+	use frame_support::storage::with_storage_layer;
+	use frame_support::traits::{
+		schedule::{v3::Named as ScheduleNamed, DispatchTime, LOWEST_PRIORITY},
+		Contains, Currency, ExistenceRequirement, OriginTrait, ReservableCurrency, StorePreimage,
+	};
+	use frame_system::{
+		offchain::{AppCrypto, CreateSignedTransaction},
+		pallet_prelude::*,
+	};
+	use crate::{
+		nft::ClaimNfts,
+		traits::{OnClaimCreated, OnClaimRevoked, OnClaimTransferred},
+		types::{Attestation, License, PoeParameters, ScheduledAction, ScheduledOperation},
+		validator::ClaimValidator,
+	};
+	use sp_runtime::{
+		traits::{Hash, Saturating, Zero},
+		Perbill,
+	};
+    use sp_std::{marker::PhantomData, vec::Vec};
 
     #[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
         // pallet::constant 用于声明这是个链上的常量
 		#[pallet::constant]
-		/// The maximum length of claim that can be added.
+		/// The maximum length of claim that can be stored. The length of new claims is limited
+		/// by [`PoeParameters::max_claim_length`] within this bound.
 		type MaxClaimLength: Get<u32>;
+		/// Upper bound of [`PoeParameters::reveal_window`].
+		#[pallet::constant]
+		type RevealWindow: Get<BlockNumberFor<Self>>;
+		/// The maximum number of commitments that can be made in a single block.
+		#[pallet::constant]
+		type MaxCommitmentsPerBlock: Get<u32>;
+		/// The deposit reserved by `commit_claim`, returned on reveal and forfeited if the
+		/// commitment expires.
+		#[pallet::constant]
+		type CommitDeposit: Get<BalanceOf<Self>>;
+		/// Upper bound of [`PoeParameters::max_claims_per_block`].
+		#[pallet::constant]
+		type MaxClaimsPerBlock: Get<u32>;
+		/// Upper bound of [`PoeParameters::max_claims_per_account`].
+		#[pallet::constant]
+		type MaxClaimsPerAccount: Get<u32>;
+		/// Upper bound of [`PoeParameters::rate_limit_window`].
+		#[pallet::constant]
+		type RateLimitWindow: Get<BlockNumberFor<Self>>;
+		/// The key the off-chain worker signs availability attestations with.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// The origin allowed to submit availability attestations, usually
+		/// [`EnsureAvailabilitySigner`].
+		type AvailabilityOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+		/// The origin allowed to add and remove availability signers.
+		type AvailabilitySignerOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Number of blocks between two runs of the off-chain availability check.
+		#[pallet::constant]
+		type VerificationInterval: Get<BlockNumberFor<Self>>;
+		/// The maximum number of claims checked in a single off-chain worker run.
+		#[pallet::constant]
+		type MaxVerificationsPerRun: Get<u32>;
+		/// Upper bound of [`PoeParameters::max_content_length`].
+		#[pallet::constant]
+		type MaxContentLength: Get<u32>;
+		/// The origin allowed to add and remove attesters.
+		type AttesterOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The maximum number of attestations a claim can hold.
+		#[pallet::constant]
+		type MaxAttestationsPerClaim: Get<u32>;
+		/// The maximum length of an attestation type.
+		#[pallet::constant]
+		type MaxAttestationTypeLength: Get<u32>;
+		/// The origin allowed to change the pallet's parameters.
+		type ParametersOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The overarching call type, which scheduled actions are dispatched as.
+		type RuntimeCall: Parameter
+			+ From<Call<Self>>
+			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
+		/// The scheduler dispatching the actions scheduled with `schedule_transfer` and
+		/// `schedule_revoke`.
+		type Scheduler: ScheduleNamed<
+			BlockNumberFor<Self>,
+			<Self as Config>::RuntimeCall,
+			PalletsOriginOf<Self>,
+		>;
+		/// Stores the scheduled calls too large to be kept inline by the scheduler.
+		type Preimages: StorePreimage;
+		/// The currency claims are sold for with `buy_claim` and commitment deposits are
+		/// reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The share of the price of a claim paid to its creator when someone else sells it.
+		#[pallet::constant]
+		type CreatorRoyalty: Get<Perbill>;
+		/// The maximum number of licences a claim can hold.
+		#[pallet::constant]
+		type MaxLicensesPerClaim: Get<u32>;
+		/// The maximum number of licences expiring in a single block.
+		#[pallet::constant]
+		type MaxLicenseExpiriesPerBlock: Get<u32>;
+		/// Number of blocks a revocation is kept in [`Revocations`] before it is pruned.
+		#[pallet::constant]
+		type RevocationRetention: Get<BlockNumberFor<Self>>;
+		/// The maximum number of claims that can be revoked in a single block.
+		#[pallet::constant]
+		type MaxRevocationsPerBlock: Get<u32>;
+		/// The NFTs standing for claims, `()` to keep claims without NFTs.
+		type Nfts: ClaimNfts<Self::AccountId>;
+		/// The accounts allowed to create, transfer and receive claims, e.g. those with a
+		/// verified identity.
+		type ClaimantFilter: Contains<Self::AccountId>;
+		/// The format rules of new claims, `()` for any claim within `max_claim_length`.
+		type ClaimValidator: ClaimValidator;
+		/// Called whenever a claim is created, and may veto it by returning an error. `()` for
+		/// none, or a tuple of hooks.
+		type OnClaimCreated: OnClaimCreated<Self::AccountId>;
+		/// Called whenever a claim changes hands, and may veto it.
+		type OnClaimTransferred: OnClaimTransferred<Self::AccountId>;
+		/// Called whenever a claim is revoked, and may veto it.
+		type OnClaimRevoked: OnClaimRevoked<Self::AccountId>;
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 	}
 
+	/// Prefix of the off-chain database keys claim content is indexed under.
+	pub const CONTENT_KEY_PREFIX: &[u8] = b"poe::content::";
+
     const STORAGE_VERSION: StorageVersion = StorageVersion::new(13);
     
     #[pallet::pallet]
@@ -37,6 +206,219 @@ pub mod pallet {
         (T::AccountId, BlockNumberFor::<T>)
     >;
 
+	/// Revoked claims, mapping each to the account that revoked it and the block it was revoked
+	/// in. The entry is removed when the claim is created again, or `RevocationRetention` blocks
+	/// after the revocation.
+	#[pallet::storage]
+	#[pallet::getter(fn revocations)]
+	pub type Revocations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxClaimLength>,
+		(T::AccountId, BlockNumberFor<T>),
+	>;
+
+	/// Pending claim commitments, mapping `hash(who, claim, salt)` to the committer, the block
+	/// the commitment was made in and the deposit reserved for it.
+	#[pallet::storage]
+	#[pallet::getter(fn commitments)]
+	pub type Commitments<T: Config> =
+		StorageMap<_, Identity, T::Hash, (T::AccountId, BlockNumberFor<T>, BalanceOf<T>)>;
+
+	/// Commitments indexed by the block at which they expire, used to clean up commitments
+	/// that were never revealed.
+	#[pallet::storage]
+	pub type CommitmentExpiries<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<T::Hash, T::MaxCommitmentsPerBlock>,
+		ValueQuery,
+	>;
+
+	/// The number of claims created in the given block.
+	#[pallet::storage]
+	pub type ClaimsInBlock<T: Config> = StorageValue<_, (BlockNumberFor<T>, u32), ValueQuery>;
+
+	/// The blocks in which an account created its claims within the last `RateLimitWindow`.
+	#[pallet::storage]
+	pub type AccountClaims<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<BlockNumberFor<T>, T::MaxClaimsPerAccount>,
+		ValueQuery,
+	>;
+
+	/// The last block at which the content of a claim was attested to be retrievable.
+	#[pallet::storage]
+	#[pallet::getter(fn last_verified)]
+	pub type LastVerified<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxClaimLength>, BlockNumberFor<T>>;
+
+	/// Accounts of the off-chain workers allowed to submit availability attestations.
+	#[pallet::storage]
+	pub type AvailabilitySigners<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// Accounts allowed to attest claims.
+	#[pallet::storage]
+	pub type Attesters<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The attestations made on each claim.
+	#[pallet::storage]
+	pub type Attestations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxClaimLength>,
+		BoundedVec<AttestationOf<T>, T::MaxAttestationsPerClaim>,
+		ValueQuery,
+	>;
+
+	/// The action scheduled for each claim by its owner, removed when the action is dispatched
+	/// or the claim changes hands before.
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_action)]
+	pub type ScheduledActions<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxClaimLength>, ScheduledActionOf<T>>;
+
+	/// The account that created each claim, which royalties are paid to when the claim is resold.
+	#[pallet::storage]
+	#[pallet::getter(fn creator)]
+	pub type Creators<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxClaimLength>, T::AccountId>;
+
+	/// Claims listed for sale, mapping each to its seller and price. The listing is removed when
+	/// the claim changes hands.
+	#[pallet::storage]
+	#[pallet::getter(fn listing)]
+	pub type Listings<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxClaimLength>,
+		(T::AccountId, BalanceOf<T>),
+	>;
+
+	/// The account approved to transfer or revoke each claim on behalf of its owner. The approval
+	/// is removed when the claim changes hands.
+	#[pallet::storage]
+	#[pallet::getter(fn approval)]
+	pub type Approvals<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxClaimLength>, T::AccountId>;
+
+	/// The operators of each account, allowed to transfer or revoke any of its claims.
+	#[pallet::storage]
+	pub type Operators<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+	>;
+
+	/// The licences granted on each claim. Licences survive transfers of the claim and are
+	/// removed when they expire or the claim is revoked.
+	#[pallet::storage]
+	pub type Licenses<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxClaimLength>,
+		BoundedVec<LicenseOf<T>, T::MaxLicensesPerClaim>,
+		ValueQuery,
+	>;
+
+	/// The claims each account holds a licence on.
+	#[pallet::storage]
+	pub type LicensedClaims<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxClaimLength>,
+		(),
+	>;
+
+	/// Claims revoked in each block, used to prune their [`Revocations`] entries once the
+	/// retention period is over.
+	#[pallet::storage]
+	pub type RevocationExpiries<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<BoundedVec<u8, T::MaxClaimLength>, T::MaxRevocationsPerBlock>,
+		ValueQuery,
+	>;
+
+	/// Licences indexed by the block at which they expire, used to remove them in time.
+	#[pallet::storage]
+	pub type LicenseExpiries<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<
+			(BoundedVec<u8, T::MaxClaimLength>, T::AccountId),
+			T::MaxLicenseExpiriesPerBlock,
+		>,
+		ValueQuery,
+	>;
+
+	/// The parameters of the pallet in effect, the upper bounds of the configuration until set at
+	/// genesis or with `set_parameters`.
+	#[pallet::storage]
+	#[pallet::getter(fn parameters)]
+	pub type Parameters<T: Config> =
+		StorageValue<_, ParametersOf<T>, ValueQuery, MaxParameters<T>>;
+
+	/// The upper bounds of the parameters, taken from the configuration.
+	#[pallet::type_value]
+	pub fn MaxParameters<T: Config>() -> ParametersOf<T> {
+		PoeParameters {
+			max_claim_length: T::MaxClaimLength::get(),
+			reveal_window: T::RevealWindow::get(),
+			max_claims_per_block: T::MaxClaimsPerBlock::get(),
+			max_claims_per_account: T::MaxClaimsPerAccount::get(),
+			rate_limit_window: T::RateLimitWindow::get(),
+			max_content_length: T::MaxContentLength::get(),
+		}
+	}
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// The initial parameters, the upper bounds of the configuration if not given.
+		pub parameters: Option<ParametersOf<T>>,
+		/// Claims existing from the genesis block on, with their owners. Their NFTs are minted
+		/// like those of other claims, so the owners must be able to pay for them.
+		pub claims: Vec<(Vec<u8>, T::AccountId)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			if let Some(parameters) = &self.parameters {
+				assert!(
+					parameters.is_within(&MaxParameters::<T>::get()),
+					"PoE parameters exceed the bounds of the configuration"
+				);
+				Parameters::<T>::put(parameters);
+			}
+
+			T::Nfts::create_collection().expect("The NFT collection of claims must be creatable");
+			for (claim, owner) in &self.claims {
+				let bounded_claim = Pallet::<T>::new_claim(claim.clone())
+					.expect("Genesis claims must be valid new claims");
+				assert!(!Proofs::<T>::contains_key(&bounded_claim), "Duplicate genesis claim");
+				T::Nfts::mint(&bounded_claim, owner)
+					.expect("The NFTs of genesis claims must be mintable");
+				Proofs::<T>::insert(
+					&bounded_claim,
+					(owner.clone(), BlockNumberFor::<T>::default()),
+				);
+				Creators::<T>::insert(&bounded_claim, owner);
+			}
+		}
+	}
+
    	// 定义事件
 	#[pallet::event]
 	// 生成工具函数
@@ -45,6 +427,29 @@ pub mod pallet {
 		ClaimCreated(T::AccountId, Vec<u8>),
 		ClaimRevoked(T::AccountId, Vec<u8>),
 		ClaimTransfered(T::AccountId, T::AccountId, Vec<u8>),
+		ClaimCommitted(T::AccountId, T::Hash),
+		CommitmentExpired(T::Hash),
+		AvailabilityAttested(T::AccountId, Vec<u8>, bool),
+		AttesterAdded(T::AccountId),
+		AttesterRemoved(T::AccountId),
+		ClaimAttested(T::AccountId, Vec<u8>, Vec<u8>),
+		AttestationRevoked(T::AccountId, Vec<u8>),
+		ParametersSet(ParametersOf<T>),
+		ActionScheduled(T::AccountId, Vec<u8>, ScheduledActionOf<T>),
+		ScheduledActionCanceled(T::AccountId, Vec<u8>),
+		ClaimListed(T::AccountId, Vec<u8>, BalanceOf<T>),
+		ClaimUnlisted(T::AccountId, Vec<u8>),
+		ClaimSold(T::AccountId, T::AccountId, Vec<u8>, BalanceOf<T>),
+		RoyaltyPaid(T::AccountId, Vec<u8>, BalanceOf<T>),
+		LicenseGranted(T::AccountId, Vec<u8>, T::AccountId, Option<BlockNumberFor<T>>),
+		LicenseRevoked(T::AccountId, Vec<u8>, T::AccountId),
+		LicenseExpired(Vec<u8>, T::AccountId),
+		ApprovalSet(T::AccountId, Vec<u8>, T::AccountId),
+		ApprovalCanceled(T::AccountId, Vec<u8>),
+		ApprovalForAllSet(T::AccountId, T::AccountId, bool),
+		ClaimNftReturned(T::AccountId, T::AccountId, Vec<u8>),
+		AvailabilitySignerAdded(T::AccountId),
+		AvailabilitySignerRemoved(T::AccountId),
 	}
 
 	// 定义错误
@@ -54,59 +459,156 @@ pub mod pallet {
 		ClaimTooLong,
 		ClaimNotExist,
 		NotClaimOwner,
+		CommitmentAlreadyExist,
+		CommitmentNotExist,
+		RevealWindowExpired,
+		TooManyCommitments,
+		RateLimited,
+		TooManyAvailabilityResults,
+		ContentTooLong,
+		AttesterAlreadyExist,
+		AttesterNotExist,
+		NotAttester,
+		AttestationTypeTooLong,
+		AttestationLimitReached,
+		AttestationNotExist,
+		InvalidExpiry,
+		ParameterOutOfBounds,
+		ActionAlreadyScheduled,
+		NoScheduledAction,
+		InvalidScheduleTime,
+		NotListed,
+		PriceTooHigh,
+		CannotBuyOwnClaim,
+		TooManyLicenses,
+		TooManyLicenseExpiries,
+		LicenseNotExist,
+		IdentityRequired,
+		InvalidClaimFormat,
+		ApproveToOwner,
+		NoApproval,
+		AvailabilitySignerAlreadyExist,
+		AvailabilitySignerNotExist,
+		TooManyRevocations,
 	}
 
 	// 用于定义回调函数，在区块的不同时期执行
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			// 清理在揭示窗口内没有被揭示的承诺
+			let expired = CommitmentExpiries::<T>::take(n);
+			let mut removed = 0u64;
+			for commitment in expired.iter() {
+				if let Some((who, _, deposit)) = Commitments::<T>::take(commitment) {
+					// 未揭示的承诺没收押金，否则占用承诺名额没有代价
+					let _ = T::Currency::slash_reserved(&who, deposit);
+					removed += 1;
+					Self::deposit_event(Event::CommitmentExpired(*commitment));
+				}
+			}
+
+			// 清理到期的许可
+			let licenses = LicenseExpiries::<T>::take(n);
+			let mut expired_licenses = 0u64;
+			for (claim, licensee) in licenses.iter() {
+				if Self::expire_license(claim, licensee, n) {
+					expired_licenses += 1;
+				}
+			}
+
+			// 清理保留期已过的撤销记录，同一存证之后又被撤销时保留新的记录
+			let revoked_at = n.saturating_sub(T::RevocationRetention::get());
+			let revocations = RevocationExpiries::<T>::take(n);
+			let mut pruned = 0u64;
+			for claim in revocations.iter() {
+				if matches!(Revocations::<T>::get(claim), Some((_, at)) if at == revoked_at) {
+					Revocations::<T>::remove(claim);
+					pruned += 1;
+				}
+			}
+
+			T::DbWeight::get().reads_writes(1 + expired.len() as u64 + removed, 1 + 2 * removed) +
+				T::DbWeight::get()
+					.reads_writes(1 + licenses.len() as u64, 1 + 2 * expired_licenses) +
+				T::DbWeight::get().reads_writes(1 + revocations.len() as u64, 1 + pruned)
+		}
+
+		fn offchain_worker(n: BlockNumberFor<T>) {
+			if let Err(e) = Self::check_availability(n) {
+				log::warn!(target: "runtime::poe", "availability check failed: {}", e);
+			}
+		}
+	}
 
 	// 定义可调用函数
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
-		#[pallet::weight(0)]
+		#[pallet::weight(Pallet::<T>::create_weight())]
 		pub fn create_claim(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResultWithPostInfo {
 			// 验证签名
 			let sender = ensure_signed(origin)?;
 
 			// 尝试从 Vec<u8> 转换为 BoundedVec<u8, T::MaxClaimLength>
-			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
-				.map_err(|_| Error::<T>::ClaimTooLong)?;
+			let bounded_claim = Self::new_claim(claim.clone())?;
 
 			// 验证是否已经存储过
 			ensure!(!Proofs::<T>::contains_key(&bounded_claim), Error::<T>::ProofAlreadyExist);
 
-			Proofs::<T>::insert(
-				&bounded_claim,
-				(sender.clone(), frame_system::Pallet::<T>::block_number()),
-			);
-
-			Self::deposit_event(Event::ClaimCreated(sender, claim));
+			Self::note_claim_created(&sender)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			Self::do_create_claim(sender, bounded_claim, now)?;
 
 			Ok(().into())
 		}
 
         #[pallet::call_index(1)]
-		#[pallet::weight(0)]
+		#[pallet::weight(Pallet::<T>::sync_owner_weight()
+			.saturating_add(Pallet::<T>::nft_weight())
+			.saturating_add(Pallet::<T>::clear_claim_state_weight())
+			.saturating_add(T::DbWeight::get().reads_writes(3, 4))
+			.saturating_add(T::OnClaimRevoked::weight()))]
 		pub fn revoke_claim(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 
 			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
 				.map_err(|_| Error::<T>::ClaimTooLong)?;
 
-			let (owner, _) = Proofs::<T>::get(&bounded_claim).ok_or(Error::<T>::ClaimNotExist)?;
+			let (owner, _) = Self::sync_owner(&bounded_claim)?;
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(
+				Self::is_approved_or_owner(&bounded_claim, &owner, &sender),
+				Error::<T>::NotClaimOwner
+			);
 
+			T::Nfts::burn(&bounded_claim)?;
 			Proofs::<T>::remove(&bounded_claim);
+			Creators::<T>::remove(&bounded_claim);
+			Self::clear_claim_state(&bounded_claim);
+			let now = frame_system::Pallet::<T>::block_number();
+			RevocationExpiries::<T>::try_mutate(
+				now.saturating_add(T::RevocationRetention::get()),
+				|expiries| {
+					expiries
+						.try_push(bounded_claim.clone())
+						.map_err(|_| Error::<T>::TooManyRevocations)
+				},
+			)?;
+			Revocations::<T>::insert(&bounded_claim, (owner.clone(), now));
+
+			T::OnClaimRevoked::on_claim_revoked(&bounded_claim, &owner)?;
 
-			Self::deposit_event(Event::ClaimRevoked(sender, claim));
+			Self::deposit_event(Event::ClaimRevoked(owner, claim));
 
 			Ok(().into())
 		}
 
         #[pallet::call_index(2)]
-		#[pallet::weight(0)]
+		// 同步 NFT 所有者和转移存证各可能调用一次转移钩子
+		#[pallet::weight(Pallet::<T>::sync_owner_weight()
+			.saturating_add(Pallet::<T>::transfer_weight())
+			.saturating_add(T::DbWeight::get().reads(2)))]
 		pub fn transfer_claim(
 			origin: OriginFor<T>,
 			claim: Vec<u8>,
@@ -118,16 +620,1126 @@ pub mod pallet {
 			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
 				.map_err(|_| Error::<T>::ClaimTooLong)?;
 
-			let (owner, _) = Proofs::<T>::get(&bounded_claim).ok_or(Error::<T>::ClaimNotExist)?;
+			let (owner, created_at) = Self::sync_owner(&bounded_claim)?;
+
+			ensure!(
+				Self::is_approved_or_owner(&bounded_claim, &owner, &sender),
+				Error::<T>::NotClaimOwner
+			);
+			ensure!(T::ClaimantFilter::contains(&sender), Error::<T>::IdentityRequired);
+
+			Self::do_transfer_claim(&bounded_claim, owner, dest, created_at)?;
+
+			Ok(().into())
+		}
+
+		/// Commit to a claim without revealing it, so the claim cannot be front-run while the
+		/// transaction sits in the pool. `commitment` must be [`Pallet::commitment_of`] the
+		/// sender, the claim and a secret salt.
+		///
+		/// Reserves `CommitDeposit`, returned on reveal and forfeited if the commitment expires.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 3))]
+		pub fn commit_claim(
+			origin: OriginFor<T>,
+			commitment: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(
+				!Commitments::<T>::contains_key(&commitment),
+				Error::<T>::CommitmentAlreadyExist
+			);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let expires_at =
+				now.saturating_add(Self::parameters().reveal_window).saturating_add(1u32.into());
+			CommitmentExpiries::<T>::try_mutate(expires_at, |commitments| {
+				commitments.try_push(commitment).map_err(|_| Error::<T>::TooManyCommitments)
+			})?;
+			let deposit = T::CommitDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
+			Commitments::<T>::insert(&commitment, (sender.clone(), now, deposit));
+
+			Self::deposit_event(Event::ClaimCommitted(sender, commitment));
+
+			Ok(().into())
+		}
+
+		/// Reveal a claim previously committed with `commit_claim`. The claim is recorded as
+		/// created at the block of the commitment, and takes over any claim of the same hash
+		/// that was created after that block. The claim taken over is revoked, along with its
+		/// licences and attestations.
+		#[pallet::call_index(4)]
+		#[pallet::weight(Pallet::<T>::create_weight()
+			.saturating_add(Pallet::<T>::clear_claim_state_weight())
+			.saturating_add(Pallet::<T>::claimant_filter_weight())
+			.saturating_add(T::DbWeight::get().reads_writes(5, 2))
+			.saturating_add(T::OnClaimRevoked::weight()))]
+		pub fn reveal_claim(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			salt: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = Self::new_claim(claim.clone())?;
+
+			let commitment = Self::commitment_of(&sender, &claim, &salt);
+			let (_, committed_at, deposit) =
+				Commitments::<T>::get(&commitment).ok_or(Error::<T>::CommitmentNotExist)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				now <= committed_at.saturating_add(Self::parameters().reveal_window),
+				Error::<T>::RevealWindowExpired
+			);
+
+			// 只有在承诺之后创建的存证才会被覆盖
+			let displaced = match Self::current_owner(&bounded_claim) {
+				Some((owner, created_at)) => {
+					ensure!(created_at > committed_at, Error::<T>::ProofAlreadyExist);
+					Some(owner)
+				},
+				None => None,
+			};
+
+			Self::note_claim_created(&sender)?;
+			Commitments::<T>::remove(&commitment);
+			T::Currency::unreserve(&sender, deposit);
+			if let Some(displaced) = displaced {
+				// 被接管的存证视同撤销，其许可、背书和内容都不再有效
+				Self::clear_claim_state(&bounded_claim);
+				T::OnClaimRevoked::on_claim_revoked(&bounded_claim, &displaced)?;
+				Self::deposit_event(Event::ClaimRevoked(displaced, claim));
+			}
+			Self::do_create_claim(sender, bounded_claim, committed_at)?;
+
+			Ok(().into())
+		}
+
+		/// Record whether the content of each claim could be retrieved and matched its hash.
+		/// Submitted by the off-chain worker.
+		#[pallet::call_index(5)]
+		#[pallet::weight(
+			T::DbWeight::get().reads_writes(1 + results.len() as u64, results.len() as u64)
+		)]
+		pub fn submit_availability(
+			origin: OriginFor<T>,
+			results: Vec<(Vec<u8>, bool)>,
+		) -> DispatchResultWithPostInfo {
+			let sender = T::AvailabilityOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				results.len() as u32 <= T::MaxVerificationsPerRun::get(),
+				Error::<T>::TooManyAvailabilityResults
+			);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			for (claim, available) in results {
+				let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+					.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+				// 存证可能在链下工作机读取之后被撤销
+				if !Proofs::<T>::contains_key(&bounded_claim) {
+					continue
+				}
+				if available {
+					LastVerified::<T>::insert(&bounded_claim, now);
+				}
+
+				Self::deposit_event(Event::AvailabilityAttested(sender.clone(), claim, available));
+			}
+
+			Ok(().into())
+		}
+
+		/// Create a claim for the blake2-256 hash of `content`, and keep `content` in the
+		/// off-chain database of nodes running with `--enable-offchain-indexing` so it can be
+		/// read back with the `poe_getContent` RPC. Only the hash is kept in on-chain state.
+		#[pallet::call_index(6)]
+		#[pallet::weight(Pallet::<T>::create_weight())]
+		pub fn create_claim_with_content(
+			origin: OriginFor<T>,
+			content: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(
+				content.len() as u32 <= Self::parameters().max_content_length,
+				Error::<T>::ContentTooLong
+			);
+
+			let claim = sp_io::hashing::blake2_256(&content).to_vec();
+			let bounded_claim = Self::new_claim(claim)?;
+
+			ensure!(!Proofs::<T>::contains_key(&bounded_claim), Error::<T>::ProofAlreadyExist);
+
+			Self::note_claim_created(&sender)?;
+			sp_io::offchain_index::set(&Self::content_key(&bounded_claim), &content);
+			let now = frame_system::Pallet::<T>::block_number();
+			Self::do_create_claim(sender, bounded_claim, now)?;
+
+			Ok(().into())
+		}
+
+		/// Allow `who` to attest claims.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn add_attester(origin: OriginFor<T>, who: T::AccountId) -> DispatchResultWithPostInfo {
+			T::AttesterOrigin::ensure_origin(origin)?;
+
+			ensure!(!Attesters::<T>::contains_key(&who), Error::<T>::AttesterAlreadyExist);
+			Attesters::<T>::insert(&who, ());
+
+			Self::deposit_event(Event::AttesterAdded(who));
+
+			Ok(().into())
+		}
+
+		/// Stop `who` from attesting claims. Its existing attestations are no longer returned by
+		/// [`Pallet::active_attestations`].
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn remove_attester(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::AttesterOrigin::ensure_origin(origin)?;
+
+			Attesters::<T>::take(&who).ok_or(Error::<T>::AttesterNotExist)?;
+
+			Self::deposit_event(Event::AttesterRemoved(who));
+
+			Ok(().into())
+		}
+
+		/// Endorse `claim` as a registered attester, replacing any earlier attestation of the
+		/// sender on the claim. The attestation holds until `expires_at`, if given.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(3, 1))]
+		pub fn attest_claim(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			attestation_type: Vec<u8>,
+			expires_at: Option<BlockNumberFor<T>>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Attesters::<T>::contains_key(&sender), Error::<T>::NotAttester);
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+			ensure!(Proofs::<T>::contains_key(&bounded_claim), Error::<T>::ClaimNotExist);
+
+			let bounded_type = BoundedVec::try_from(attestation_type.clone())
+				.map_err(|_| Error::<T>::AttestationTypeTooLong)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				expires_at.map_or(true, |expires_at| expires_at > now),
+				Error::<T>::InvalidExpiry
+			);
+
+			Attestations::<T>::try_mutate(&bounded_claim, |attestations| {
+				// 先清理过期的和同一个证明人的旧背书
+				attestations.retain(|a| a.is_active(&now) && a.attester != sender);
+				attestations
+					.try_push(Attestation {
+						attester: sender.clone(),
+						attestation_type: bounded_type,
+						attested_at: now,
+						expires_at,
+					})
+					.map_err(|_| Error::<T>::AttestationLimitReached)
+			})?;
+
+			Self::deposit_event(Event::ClaimAttested(sender, claim, attestation_type));
+
+			Ok(().into())
+		}
+
+		/// Withdraw the sender's attestation of `claim`.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn revoke_attestation(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			Attestations::<T>::try_mutate_exists(&bounded_claim, |maybe_attestations| {
+				let attestations =
+					maybe_attestations.as_mut().ok_or(Error::<T>::AttestationNotExist)?;
+				let index = attestations
+					.iter()
+					.position(|a| a.attester == sender)
+					.ok_or(Error::<T>::AttestationNotExist)?;
+				attestations.remove(index);
+				if attestations.is_empty() {
+					*maybe_attestations = None;
+				}
+				Ok::<_, Error<T>>(())
+			})?;
+
+			Self::deposit_event(Event::AttestationRevoked(sender, claim));
+
+			Ok(().into())
+		}
+
+		/// Replace the parameters of the pallet. Each must be within the matching bound of the
+		/// configuration.
+		///
+		/// Lower limits only apply to claims created afterwards, and a shorter reveal window also
+		/// applies to pending commitments.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_parameters(
+			origin: OriginFor<T>,
+			parameters: ParametersOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::ParametersOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				parameters.is_within(&MaxParameters::<T>::get()),
+				Error::<T>::ParameterOutOfBounds
+			);
+			Parameters::<T>::put(&parameters);
+
+			Self::deposit_event(Event::ParametersSet(parameters));
+
+			Ok(().into())
+		}
+
+		/// Transfer `claim` to `dest` at block `when`, as if the owner called `transfer_claim`
+		/// then. The transfer is dropped if the claim changes hands before.
+		#[pallet::call_index(12)]
+		#[pallet::weight(Pallet::<T>::schedule_weight())]
+		pub fn schedule_transfer(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			dest: T::AccountId,
+			when: BlockNumberFor<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let call = Call::<T>::transfer_claim { claim: claim.clone(), dest: dest.clone() };
+			let action = ScheduledAction { when, operation: ScheduledOperation::Transfer(dest) };
+			Self::schedule(sender, claim, action, call)
+		}
+
+		/// Revoke `claim` at block `when`, as if the owner called `revoke_claim` then. The
+		/// revocation is dropped if the claim changes hands before.
+		#[pallet::call_index(13)]
+		#[pallet::weight(Pallet::<T>::schedule_weight())]
+		pub fn schedule_revoke(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			when: BlockNumberFor<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let call = Call::<T>::revoke_claim { claim: claim.clone() };
+			let action = ScheduledAction { when, operation: ScheduledOperation::Revoke };
+			Self::schedule(sender, claim, action, call)
+		}
+
+		/// Cancel the action scheduled for `claim`.
+		#[pallet::call_index(14)]
+		#[pallet::weight(
+			Pallet::<T>::sync_owner_weight().saturating_add(T::DbWeight::get().reads_writes(3, 3))
+		)]
+		pub fn cancel_scheduled(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			let (owner, _) = Self::sync_owner(&bounded_claim)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+
+			ScheduledActions::<T>::take(&bounded_claim).ok_or(Error::<T>::NoScheduledAction)?;
+			// 调度器已派发任务但调用失败时记录仍在，此时取消会失败，可以忽略
+			let _ = T::Scheduler::cancel_named(Self::task_name(&bounded_claim));
 
+			Self::deposit_event(Event::ScheduledActionCanceled(sender, claim));
+
+			Ok(().into())
+		}
+
+		/// Offer `claim` for sale at `price`, replacing the price of an earlier listing.
+		#[pallet::call_index(15)]
+		#[pallet::weight(
+			Pallet::<T>::sync_owner_weight().saturating_add(T::DbWeight::get().writes(1))
+		)]
+		pub fn list_claim(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			let (owner, _) = Self::sync_owner(&bounded_claim)?;
 			ensure!(owner == sender, Error::<T>::NotClaimOwner);
 
-			Proofs::<T>::insert(&bounded_claim, (dest, frame_system::Pallet::<T>::block_number()));
+			Listings::<T>::insert(&bounded_claim, (sender.clone(), price));
 
-			Self::deposit_event(Event::ClaimTransfered(owner, sender, claim));
+			Self::deposit_event(Event::ClaimListed(sender, claim, price));
+
+			Ok(().into())
+		}
+
+		/// Withdraw `claim` from sale.
+		#[pallet::call_index(16)]
+		#[pallet::weight(
+			Pallet::<T>::sync_owner_weight().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		)]
+		pub fn unlist_claim(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			Self::sync_owner(&bounded_claim)?;
+			let (seller, _) = Listings::<T>::get(&bounded_claim).ok_or(Error::<T>::NotListed)?;
+			ensure!(seller == sender, Error::<T>::NotClaimOwner);
+
+			Listings::<T>::remove(&bounded_claim);
+
+			Self::deposit_event(Event::ClaimUnlisted(sender, claim));
+
+			Ok(().into())
+		}
+
+		/// Buy a listed claim, paying its price to the seller, less the `CreatorRoyalty` share
+		/// paid to the creator of the claim if the seller did not create it. Fails if the price
+		/// is above `max_price`, so a buyer cannot be front-run by a price increase.
+		#[pallet::call_index(17)]
+		// 价款和版税最多涉及买方、卖方和创建者三个账户
+		#[pallet::weight(Pallet::<T>::sync_owner_weight()
+			.saturating_add(Pallet::<T>::transfer_weight())
+			.saturating_add(Pallet::<T>::claimant_filter_weight())
+			.saturating_add(T::DbWeight::get().reads_writes(5, 3)))]
+		pub fn buy_claim(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			max_price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let buyer = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			let (owner, created_at) = Self::sync_owner(&bounded_claim)?;
+			let (seller, price) =
+				Listings::<T>::get(&bounded_claim).ok_or(Error::<T>::NotListed)?;
+			ensure!(owner == seller, Error::<T>::NotListed);
+			ensure!(buyer != seller, Error::<T>::CannotBuyOwnClaim);
+			ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+			ensure!(T::ClaimantFilter::contains(&buyer), Error::<T>::IdentityRequired);
+
+			// 转售时把一部分价款付给存证的创建者
+			let mut proceeds = price;
+			if let Some(creator) = Creators::<T>::get(&bounded_claim) {
+				let royalty = T::CreatorRoyalty::get() * price;
+				if creator != seller && !royalty.is_zero() {
+					T::Currency::transfer(
+						&buyer,
+						&creator,
+						royalty,
+						ExistenceRequirement::KeepAlive,
+					)?;
+					proceeds = price.saturating_sub(royalty);
+					Self::deposit_event(Event::RoyaltyPaid(creator, claim.clone(), royalty));
+				}
+			}
+			T::Currency::transfer(&buyer, &seller, proceeds, ExistenceRequirement::KeepAlive)?;
+
+			Self::do_transfer_claim(&bounded_claim, seller.clone(), buyer.clone(), created_at)?;
+
+			Self::deposit_event(Event::ClaimSold(seller, buyer, claim, price));
+
+			Ok(().into())
+		}
+
+		/// Grant `licensee` usage rights on `claim` under the terms hashing to `terms_hash`, until
+		/// `expires_at` if given. Replaces an earlier licence of `licensee` on the claim.
+		#[pallet::call_index(18)]
+		#[pallet::weight(
+			Pallet::<T>::sync_owner_weight().saturating_add(T::DbWeight::get().reads_writes(2, 3))
+		)]
+		pub fn grant_license(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			licensee: T::AccountId,
+			terms_hash: T::Hash,
+			expires_at: Option<BlockNumberFor<T>>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			let (owner, _) = Self::sync_owner(&bounded_claim)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				expires_at.map_or(true, |expires_at| expires_at > now),
+				Error::<T>::InvalidExpiry
+			);
+
+			Licenses::<T>::try_mutate(&bounded_claim, |licenses| {
+				// 已过期但还没被清理的许可不再占用名额
+				licenses.retain(|l| l.is_active(&now) && l.licensee != licensee);
+				licenses
+					.try_push(License {
+						licensee: licensee.clone(),
+						terms_hash,
+						granted_at: now,
+						expires_at,
+					})
+					.map_err(|_| Error::<T>::TooManyLicenses)
+			})?;
+			if let Some(expires_at) = expires_at {
+				LicenseExpiries::<T>::try_mutate(expires_at, |expiries| {
+					expiries
+						.try_push((bounded_claim.clone(), licensee.clone()))
+						.map_err(|_| Error::<T>::TooManyLicenseExpiries)
+				})?;
+			}
+			LicensedClaims::<T>::insert(&licensee, &bounded_claim, ());
+
+			Self::deposit_event(Event::LicenseGranted(sender, claim, licensee, expires_at));
+
+			Ok(().into())
+		}
+
+		/// Withdraw the licence of `licensee` on `claim`.
+		#[pallet::call_index(19)]
+		#[pallet::weight(
+			Pallet::<T>::sync_owner_weight().saturating_add(T::DbWeight::get().reads_writes(1, 2))
+		)]
+		pub fn revoke_license(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			licensee: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			let (owner, _) = Self::sync_owner(&bounded_claim)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+
+			Licenses::<T>::try_mutate_exists(&bounded_claim, |maybe_licenses| {
+				let licenses = maybe_licenses.as_mut().ok_or(Error::<T>::LicenseNotExist)?;
+				let index = licenses
+					.iter()
+					.position(|l| l.licensee == licensee)
+					.ok_or(Error::<T>::LicenseNotExist)?;
+				licenses.remove(index);
+				if licenses.is_empty() {
+					*maybe_licenses = None;
+				}
+				Ok::<_, Error<T>>(())
+			})?;
+			LicensedClaims::<T>::remove(&licensee, &bounded_claim);
+
+			Self::deposit_event(Event::LicenseRevoked(sender, claim, licensee));
+
+			Ok(().into())
+		}
+
+		/// Move `claim` to the owner of its NFT, if the NFT was transferred on its own. Every
+		/// call on the claim does so first, this lets anyone bring `Proofs` up to date. The NFT
+		/// is returned to the owner instead if its holder fails `ClaimantFilter` or
+		/// `OnClaimTransferred` vetoes the transfer.
+		#[pallet::call_index(20)]
+		#[pallet::weight(Pallet::<T>::sync_owner_weight())]
+		pub fn sync_claim_owner(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim)
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+			Self::sync_owner(&bounded_claim)?;
+
+			Ok(().into())
+		}
+
+		/// Approve `delegate` to transfer or revoke `claim` on behalf of its owner, replacing the
+		/// previous approval. May be called by the owner or one of its operators.
+		#[pallet::call_index(21)]
+		#[pallet::weight(
+			Pallet::<T>::sync_owner_weight().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		)]
+		pub fn approve(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			delegate: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			let (owner, _) = Self::sync_owner(&bounded_claim)?;
+			ensure!(
+				owner == sender || Operators::<T>::contains_key(&owner, &sender),
+				Error::<T>::NotClaimOwner
+			);
+			ensure!(delegate != owner, Error::<T>::ApproveToOwner);
+
+			Approvals::<T>::insert(&bounded_claim, &delegate);
+
+			Self::deposit_event(Event::ApprovalSet(owner, claim, delegate));
+
+			Ok(().into())
+		}
+
+		/// Withdraw the approval on `claim`. May be called by the owner or one of its operators.
+		#[pallet::call_index(22)]
+		#[pallet::weight(
+			Pallet::<T>::sync_owner_weight().saturating_add(T::DbWeight::get().reads_writes(2, 1))
+		)]
+		pub fn cancel_approval(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			let (owner, _) = Self::sync_owner(&bounded_claim)?;
+			ensure!(
+				owner == sender || Operators::<T>::contains_key(&owner, &sender),
+				Error::<T>::NotClaimOwner
+			);
+			Approvals::<T>::take(&bounded_claim).ok_or(Error::<T>::NoApproval)?;
+
+			Self::deposit_event(Event::ApprovalCanceled(owner, claim));
+
+			Ok(().into())
+		}
+
+		/// Allow or disallow `operator` to transfer or revoke any claim of the sender, and to
+		/// approve others to do so.
+		#[pallet::call_index(23)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_approval_for_all(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			approved: bool,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(operator != sender, Error::<T>::ApproveToOwner);
+
+			if approved {
+				Operators::<T>::insert(&sender, &operator, ());
+			} else {
+				Operators::<T>::remove(&sender, &operator);
+			}
+
+			Self::deposit_event(Event::ApprovalForAllSet(sender, operator, approved));
+
+			Ok(().into())
+		}
+
+		/// Allow `who` to submit availability attestations, as the account of the `poe!` key of
+		/// an off-chain worker.
+		#[pallet::call_index(24)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn add_availability_signer(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::AvailabilitySignerOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				!AvailabilitySigners::<T>::contains_key(&who),
+				Error::<T>::AvailabilitySignerAlreadyExist
+			);
+			AvailabilitySigners::<T>::insert(&who, ());
+
+			Self::deposit_event(Event::AvailabilitySignerAdded(who));
+
+			Ok(().into())
+		}
+
+		/// Stop `who` from submitting availability attestations.
+		#[pallet::call_index(25)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn remove_availability_signer(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::AvailabilitySignerOrigin::ensure_origin(origin)?;
+
+			AvailabilitySigners::<T>::take(&who).ok_or(Error::<T>::AvailabilitySignerNotExist)?;
+
+			Self::deposit_event(Event::AvailabilitySignerRemoved(who));
 
 			Ok(().into())
 		}
 	}
 
+	impl<T: Config> Pallet<T> {
+		/// The commitment `who` has to submit to `commit_claim` before revealing `claim` with
+		/// `salt`.
+		pub fn commitment_of(who: &T::AccountId, claim: &[u8], salt: &[u8]) -> T::Hash {
+			T::Hashing::hash_of(&(who, claim, salt))
+		}
+
+		/// The attestations of `claim` that have not expired and were made by attesters that are
+		/// still registered.
+		pub fn active_attestations(claim: &[u8]) -> Vec<AttestationOf<T>> {
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.to_vec());
+			let bounded_claim = match bounded_claim {
+				Ok(bounded_claim) => bounded_claim,
+				Err(_) => return Vec::new(),
+			};
+			let now = frame_system::Pallet::<T>::block_number();
+
+			Attestations::<T>::get(&bounded_claim)
+				.into_iter()
+				.filter(|a| a.is_active(&now) && Attesters::<T>::contains_key(&a.attester))
+				.collect()
+		}
+
+		/// The licences on `claim` that have not expired.
+		pub fn active_licenses(claim: &[u8]) -> Vec<LicenseOf<T>> {
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.to_vec());
+			let bounded_claim = match bounded_claim {
+				Ok(bounded_claim) => bounded_claim,
+				Err(_) => return Vec::new(),
+			};
+			let now = frame_system::Pallet::<T>::block_number();
+
+			Licenses::<T>::get(&bounded_claim).into_iter().filter(|l| l.is_active(&now)).collect()
+		}
+
+		/// The claims `licensee` holds a licence on that has not expired, with the licences.
+		pub fn licenses_of(licensee: &T::AccountId) -> Vec<(Vec<u8>, LicenseOf<T>)> {
+			let now = frame_system::Pallet::<T>::block_number();
+
+			LicensedClaims::<T>::iter_key_prefix(licensee)
+				.filter_map(|claim| {
+					let license = Licenses::<T>::get(&claim)
+						.into_iter()
+						.find(|l| l.licensee == *licensee && l.is_active(&now))?;
+					Some((claim.into_inner(), license))
+				})
+				.collect()
+		}
+
+		/// The off-chain database key the content of `claim` is indexed under.
+		pub fn content_key(claim: &[u8]) -> Vec<u8> {
+			[CONTENT_KEY_PREFIX, claim].concat()
+		}
+
+		/// Check that `who` can create another claim in the current block without exceeding
+		/// the `max_claims_per_block` or `max_claims_per_account` parameters.
+		pub fn ensure_within_rate_limit(who: &T::AccountId) -> Result<(), Error<T>> {
+			let now = frame_system::Pallet::<T>::block_number();
+			let parameters = Self::parameters();
+
+			ensure!(
+				Self::claims_in_block(now) < parameters.max_claims_per_block,
+				Error::<T>::RateLimited
+			);
+			ensure!(
+				(Self::recent_claims(who, now).len() as u32) < parameters.max_claims_per_account,
+				Error::<T>::RateLimited
+			);
+
+			Ok(())
+		}
+
+		/// Bound a claim that is about to be created, which must not be longer than the
+		/// `max_claim_length` parameter and must be accepted by [`Config::ClaimValidator`].
+		pub(crate) fn new_claim(
+			claim: Vec<u8>,
+		) -> Result<BoundedVec<u8, T::MaxClaimLength>, Error<T>> {
+			ensure!(
+				claim.len() as u32 <= Self::parameters().max_claim_length,
+				Error::<T>::ClaimTooLong
+			);
+			ensure!(T::ClaimValidator::validate(&claim), Error::<T>::InvalidClaimFormat);
+			BoundedVec::try_from(claim).map_err(|_| Error::<T>::ClaimTooLong)
+		}
+
+		fn note_claim_created(who: &T::AccountId) -> DispatchResult {
+			Self::ensure_within_rate_limit(who)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+			ClaimsInBlock::<T>::put((now, Self::claims_in_block(now) + 1));
+
+			let mut recent = Self::recent_claims(who, now);
+			recent.try_push(now).map_err(|_| Error::<T>::RateLimited)?;
+			AccountClaims::<T>::insert(who, recent);
+
+			Ok(())
+		}
+
+		pub(crate) fn claims_in_block(now: BlockNumberFor<T>) -> u32 {
+			let (block, count) = ClaimsInBlock::<T>::get();
+			if block == now {
+				count
+			} else {
+				0
+			}
+		}
+
+		/// The claims of `who` that still count towards its quota at block `now`.
+		fn recent_claims(
+			who: &T::AccountId,
+			now: BlockNumberFor<T>,
+		) -> BoundedVec<BlockNumberFor<T>, T::MaxClaimsPerAccount> {
+			let window = Self::parameters().rate_limit_window;
+			let mut recent = AccountClaims::<T>::get(who);
+			recent.retain(|created_at| created_at.saturating_add(window) > now);
+			recent
+		}
+
+		/// The name of the scheduler task dispatching the action scheduled for `claim`.
+		pub fn task_name(claim: &[u8]) -> [u8; 32] {
+			(b"poe/schd", claim).using_encoded(sp_io::hashing::blake2_256)
+		}
+
+		fn schedule(
+			who: T::AccountId,
+			claim: Vec<u8>,
+			action: ScheduledActionOf<T>,
+			call: Call<T>,
+		) -> DispatchResultWithPostInfo {
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			let (owner, _) = Self::sync_owner(&bounded_claim)?;
+			ensure!(owner == who, Error::<T>::NotClaimOwner);
+			let now = frame_system::Pallet::<T>::block_number();
+			if let Some(scheduled) = ScheduledActions::<T>::get(&bounded_claim) {
+				// 已到期的记录说明派发的调用失败了，可以覆盖
+				ensure!(scheduled.when <= now, Error::<T>::ActionAlreadyScheduled);
+				let _ = T::Scheduler::cancel_named(Self::task_name(&bounded_claim));
+			}
+			ensure!(action.when > now, Error::<T>::InvalidScheduleTime);
+
+			// 到期时以存证所有者的身份调用
+			let origin = T::RuntimeOrigin::signed(who.clone()).caller().clone();
+			let call = T::Preimages::bound(<T as Config>::RuntimeCall::from(call))?;
+			T::Scheduler::schedule_named(
+				Self::task_name(&bounded_claim),
+				DispatchTime::At(action.when),
+				None,
+				LOWEST_PRIORITY,
+				origin,
+				call,
+			)?;
+			ScheduledActions::<T>::insert(&bounded_claim, &action);
+
+			Self::deposit_event(Event::ActionScheduled(who, claim, action));
+
+			Ok(().into())
+		}
+
+		/// Remove the licence of `licensee` on `claim` if it expires at block `n`, returning
+		/// whether it did. The licence may have been revoked or granted again since.
+		fn expire_license(
+			claim: &BoundedVec<u8, T::MaxClaimLength>,
+			licensee: &T::AccountId,
+			n: BlockNumberFor<T>,
+		) -> bool {
+			let mut expired = false;
+			Licenses::<T>::mutate_exists(claim, |maybe_licenses| {
+				if let Some(licenses) = maybe_licenses {
+					licenses.retain(|l| {
+						let expires = l.licensee == *licensee && l.expires_at == Some(n);
+						expired |= expires;
+						!expires
+					});
+					if licenses.is_empty() {
+						*maybe_licenses = None;
+					}
+				}
+			});
+
+			if expired {
+				LicensedClaims::<T>::remove(licensee, claim);
+				Self::deposit_event(Event::LicenseExpired(claim.to_vec(), licensee.clone()));
+			}
+			expired
+		}
+
+		/// The owner and creation block of `claim`. The owner of the NFT of the claim takes
+		/// precedence over `Proofs`, which lags behind while the NFT is transferred on its own,
+		/// unless it fails `ClaimantFilter`.
+		pub(crate) fn current_owner(
+			claim: &BoundedVec<u8, T::MaxClaimLength>,
+		) -> Option<(T::AccountId, BlockNumberFor<T>)> {
+			let (owner, created_at) = Proofs::<T>::get(claim)?;
+			let holder = T::Nfts::owner(claim).filter(|holder| T::ClaimantFilter::contains(holder));
+			Some((holder.unwrap_or(owner), created_at))
+		}
+
+		/// Whether `who` may transfer or revoke `claim` of `owner`: the owner itself, the account
+		/// approved for the claim or an operator of the owner.
+		pub fn is_approved_or_owner(
+			claim: &BoundedVec<u8, T::MaxClaimLength>,
+			owner: &T::AccountId,
+			who: &T::AccountId,
+		) -> bool {
+			who == owner ||
+				Approvals::<T>::get(claim).as_ref() == Some(who) ||
+				Operators::<T>::contains_key(owner, who)
+		}
+
+		/// The owner and creation block of `claim`, after moving the claim to the owner of its
+		/// NFT if the NFT was transferred on its own.
+		///
+		/// A holder rejected by `ClaimantFilter` or a transfer vetoed by `OnClaimTransferred` sends
+		/// the NFT back to the owner instead of failing, otherwise every later call on the claim
+		/// would fail the same way.
+		pub(crate) fn sync_owner(
+			claim: &BoundedVec<u8, T::MaxClaimLength>,
+		) -> Result<(T::AccountId, BlockNumberFor<T>), DispatchError> {
+			let (owner, created_at) = Proofs::<T>::get(claim).ok_or(Error::<T>::ClaimNotExist)?;
+			let holder = match T::Nfts::owner(claim) {
+				Some(holder) if holder != owner => holder,
+				_ => return Ok((owner, created_at)),
+			};
+
+			let synced = T::ClaimantFilter::contains(&holder) &&
+				with_storage_layer(|| {
+					Self::set_owner(claim, owner.clone(), holder.clone(), created_at)
+				})
+				.is_ok();
+			if synced {
+				return Ok((holder, created_at))
+			}
+
+			T::Nfts::transfer(claim, &owner)?;
+			Self::deposit_event(Event::ClaimNftReturned(holder, owner.clone(), claim.to_vec()));
+			Ok((owner, created_at))
+		}
+
+		/// Move `claim` and its NFT from `from` to `to`.
+		pub(crate) fn do_transfer_claim(
+			claim: &BoundedVec<u8, T::MaxClaimLength>,
+			from: T::AccountId,
+			to: T::AccountId,
+			created_at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			ensure!(T::ClaimantFilter::contains(&to), Error::<T>::IdentityRequired);
+			T::Nfts::transfer(claim, &to)?;
+			Self::set_owner(claim, from, to, created_at)
+		}
+
+		fn set_owner(
+			claim: &BoundedVec<u8, T::MaxClaimLength>,
+			from: T::AccountId,
+			to: T::AccountId,
+			created_at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			// 保留存证的创建区块，否则 `reveal_claim` 可以覆盖被转移过的旧存证
+			Proofs::<T>::insert(claim, (to.clone(), created_at));
+			Self::clear_owner_state(claim);
+			T::OnClaimTransferred::on_claim_transferred(claim, &from, &to)?;
+
+			Self::deposit_event(Event::ClaimTransfered(from, to, claim.to_vec()));
+			Ok(())
+		}
+
+		/// Drop the listing, the approval and the scheduled action of `claim` once the claim
+		/// changed hands.
+		fn clear_owner_state(claim: &BoundedVec<u8, T::MaxClaimLength>) {
+			Listings::<T>::remove(claim);
+			Approvals::<T>::remove(claim);
+			if ScheduledActions::<T>::take(claim).is_some() {
+				// 调度器在派发前已经移除了任务，此时取消会失败，可以忽略
+				let _ = T::Scheduler::cancel_named(Self::task_name(claim));
+			}
+		}
+
+		/// Drop everything attached to `claim` when it is revoked or taken over by
+		/// `reveal_claim`.
+		fn clear_claim_state(claim: &BoundedVec<u8, T::MaxClaimLength>) {
+			Self::clear_owner_state(claim);
+			for license in Licenses::<T>::take(claim) {
+				LicensedClaims::<T>::remove(&license.licensee, claim);
+			}
+			LastVerified::<T>::remove(claim);
+			sp_io::offchain_index::clear(&Self::content_key(claim));
+			Attestations::<T>::remove(claim);
+		}
+
+		/// Weight of minting, transferring or burning the NFT of a claim, counting the storage
+		/// items `pallet_nfts` reads and writes for it, deposits included.
+		pub(crate) fn nft_weight() -> Weight {
+			T::DbWeight::get().reads_writes(5, 6)
+		}
+
+		/// Weight of checking an account against `ClaimantFilter`, e.g. reading the registrars
+		/// and the identity of the account in `pallet_identity`.
+		pub(crate) fn claimant_filter_weight() -> Weight {
+			T::DbWeight::get().reads(2)
+		}
+
+		/// Weight of [`Self::clear_owner_state`], canceling the scheduled action included.
+		pub(crate) fn clear_owner_state_weight() -> Weight {
+			T::DbWeight::get().reads_writes(3, 5)
+		}
+
+		/// Weight of [`Self::clear_claim_state`] with the most licences a claim can hold.
+		pub(crate) fn clear_claim_state_weight() -> Weight {
+			let licenses = T::MaxLicensesPerClaim::get() as u64;
+			Self::clear_owner_state_weight()
+				.saturating_add(T::DbWeight::get().reads_writes(1, 3 + licenses))
+		}
+
+		/// Weight of [`Self::set_owner`], the transfer hook included.
+		pub(crate) fn set_owner_weight() -> Weight {
+			T::DbWeight::get()
+				.writes(1)
+				.saturating_add(Self::clear_owner_state_weight())
+				.saturating_add(T::OnClaimTransferred::weight())
+		}
+
+		/// Weight of [`Self::sync_owner`] at worst: the transfer to the holder of the NFT is
+		/// tried, then vetoed and the NFT sent back.
+		pub(crate) fn sync_owner_weight() -> Weight {
+			T::DbWeight::get()
+				.reads(2)
+				.saturating_add(Self::claimant_filter_weight())
+				.saturating_add(Self::set_owner_weight())
+				.saturating_add(Self::nft_weight())
+		}
+
+		/// Weight of [`Self::do_transfer_claim`].
+		pub(crate) fn transfer_weight() -> Weight {
+			Self::claimant_filter_weight()
+				.saturating_add(Self::nft_weight())
+				.saturating_add(Self::set_owner_weight())
+		}
+
+		/// Weight of [`Self::note_claim_created`] and [`Self::do_create_claim`], the creation
+		/// hook included.
+		pub(crate) fn create_weight() -> Weight {
+			T::DbWeight::get()
+				.reads_writes(5, 5)
+				.saturating_add(Self::claimant_filter_weight())
+				.saturating_add(Self::nft_weight())
+				.saturating_add(Self::clear_owner_state_weight())
+				.saturating_add(T::OnClaimCreated::weight())
+		}
+
+		/// Weight of [`Self::schedule`]: replacing the scheduled action, storing the call as a
+		/// preimage if it is too large to be kept inline and scheduling it.
+		pub(crate) fn schedule_weight() -> Weight {
+			Self::sync_owner_weight().saturating_add(T::DbWeight::get().reads_writes(7, 7))
+		}
+
+		pub(crate) fn do_create_claim(
+			who: T::AccountId,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+			created_at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			ensure!(T::ClaimantFilter::contains(&who), Error::<T>::IdentityRequired);
+			// `reveal_claim` 可能接管了别人的存证，此时转移已有的 NFT
+			match T::Nfts::owner(&claim) {
+				Some(_) => T::Nfts::transfer(&claim, &who)?,
+				None => T::Nfts::mint(&claim, &who)?,
+			}
+			Proofs::<T>::insert(&claim, (who.clone(), created_at));
+			Revocations::<T>::remove(&claim);
+			Creators::<T>::insert(&claim, &who);
+			Self::clear_owner_state(&claim);
+			T::OnClaimCreated::on_claim_created(&claim, &who)?;
+
+			Self::deposit_event(Event::ClaimCreated(who, claim.into_inner()));
+			Ok(())
+		}
+	}
+
+	/// The parameters of the pallet for its configuration.
+	pub type ParametersOf<T> = PoeParameters<BlockNumberFor<T>>;
+
+	/// A licence as stored for this pallet's configuration.
+	pub type LicenseOf<T> = License<
+		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::Hash,
+		BlockNumberFor<T>,
+	>;
+
+	/// The balance of an account in [`Config::Currency`].
+	pub type BalanceOf<T> = <<T as Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::Balance;
+
+	/// A scheduled action as stored for this pallet's configuration.
+	pub type ScheduledActionOf<T> =
+		ScheduledAction<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+	/// The origin the scheduler dispatches calls with.
+	pub type PalletsOriginOf<T> =
+		<<T as frame_system::Config>::RuntimeOrigin as OriginTrait>::PalletsOrigin;
+
+	/// An attestation as stored for this pallet's configuration.
+	pub type AttestationOf<T> = Attestation<
+		<T as frame_system::Config>::AccountId,
+		BlockNumberFor<T>,
+		BoundedVec<u8, <T as Config>::MaxAttestationTypeLength>,
+	>;
+
+	/// Ensures the origin is signed by a registered attester, returning its account.
+	pub struct EnsureAttester<T>(PhantomData<T>);
+
+	impl<T: Config> EnsureOrigin<T::RuntimeOrigin> for EnsureAttester<T> {
+		type Success = T::AccountId;
+
+		fn try_origin(o: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+			o.into().and_then(|o| match o {
+				frame_system::RawOrigin::Signed(who) if Attesters::<T>::contains_key(&who) =>
+					Ok(who),
+				r => Err(T::RuntimeOrigin::from(r)),
+			})
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+			Err(())
+		}
+	}
+
+	/// Ensures the origin is signed by a registered availability signer, returning its account.
+	pub struct EnsureAvailabilitySigner<T>(PhantomData<T>);
+
+	impl<T: Config> EnsureOrigin<T::RuntimeOrigin> for EnsureAvailabilitySigner<T> {
+		type Success = T::AccountId;
+
+		fn try_origin(o: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+			o.into().and_then(|o| match o {
+				frame_system::RawOrigin::Signed(who)
+					if AvailabilitySigners::<T>::contains_key(&who) =>
+					Ok(who),
+				r => Err(T::RuntimeOrigin::from(r)),
+			})
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+			Err(())
+		}
+	}
 }
@@ -0,0 +1,349 @@
+use crate as pallet_poe;
+use crate::{
+	ClaimNfts, ForbiddenPrefixes, MinLength, OnClaimCreated, OnClaimRevoked, OnClaimTransferred,
+};
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure, parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, Contains, EqualPrivilegeOnly},
+	weights::Weight,
+	Blake2_128Concat,
+};
+use frame_system::{offchain::AppCrypto, EnsureRoot};
+use sp_core::H256;
+use sp_runtime::{
+	testing::{TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage, DispatchError, Perbill,
+};
+use std::cell::RefCell;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Scheduler: pallet_scheduler,
+		Preimage: pallet_preimage,
+		PoeModule: pallet_poe,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type RuntimeHoldReason = ();
+	type MaxHolds = ();
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Weight::from_parts(1_000_000_000, u64::MAX);
+	pub const CreatorRoyalty: Perbill = Perbill::from_percent(10);
+	pub ForbiddenClaimPrefixes: Vec<Vec<u8>> = vec![vec![0xff]];
+}
+
+impl pallet_scheduler::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<u64>;
+	type MaxScheduledPerBlock = ConstU32<10>;
+	type WeightInfo = ();
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+impl pallet_preimage::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<u64>;
+	type BaseDeposit = ConstU64<0>;
+	type ByteDeposit = ConstU64<0>;
+}
+
+pub struct TestAuthorityId;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAuthorityId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<C> frame_system::offchain::CreateSignedTransaction<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	fn create_transaction<A: AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		_account: u64,
+		nonce: u64,
+	) -> Option<(RuntimeCall, (u64, ()))> {
+		Some((call, (nonce, ())))
+	}
+}
+
+impl pallet_poe::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxClaimLength = ConstU32<32>;
+	type RevealWindow = ConstU64<5>;
+	type MaxCommitmentsPerBlock = ConstU32<2>;
+	type CommitDeposit = ConstU64<5>;
+	type MaxClaimsPerBlock = ConstU32<3>;
+	type MaxClaimsPerAccount = ConstU32<2>;
+	type RateLimitWindow = ConstU64<3>;
+	type AuthorityId = TestAuthorityId;
+	type AvailabilityOrigin = pallet_poe::EnsureAvailabilitySigner<Test>;
+	type AvailabilitySignerOrigin = EnsureRoot<u64>;
+	type VerificationInterval = ConstU64<2>;
+	type MaxVerificationsPerRun = ConstU32<2>;
+	type MaxContentLength = ConstU32<16>;
+	type AttesterOrigin = EnsureRoot<u64>;
+	type MaxAttestationsPerClaim = ConstU32<2>;
+	type MaxAttestationTypeLength = ConstU32<8>;
+	type ParametersOrigin = EnsureRoot<u64>;
+	type RuntimeCall = RuntimeCall;
+	type Scheduler = Scheduler;
+	type Preimages = Preimage;
+	type Currency = Balances;
+	type CreatorRoyalty = CreatorRoyalty;
+	type MaxLicensesPerClaim = ConstU32<2>;
+	type MaxLicenseExpiriesPerBlock = ConstU32<2>;
+	type RevocationRetention = ConstU64<5>;
+	type MaxRevocationsPerBlock = ConstU32<2>;
+	type Nfts = MockNfts;
+	type ClaimantFilter = Claimants;
+	type ClaimValidator = (MinLength<ConstU32<1>>, ForbiddenPrefixes<ForbiddenClaimPrefixes>);
+	type OnClaimCreated = (VetoClaimChanges, RecordClaimChanges);
+	type OnClaimTransferred = (VetoClaimChanges, RecordClaimChanges);
+	type OnClaimRevoked = (VetoClaimChanges, RecordClaimChanges, ());
+}
+
+/// Every account but 8, which has no verified identity.
+pub struct Claimants;
+
+impl Contains<u64> for Claimants {
+	fn contains(who: &u64) -> bool {
+		*who != 8
+	}
+}
+
+// 存在存储中，以便随交易回滚
+#[frame_support::storage_alias]
+type NftOwners = StorageMap<MockNfts, Blake2_128Concat, Vec<u8>, u64>;
+
+/// NFTs of claims kept in storage. Account 9 cannot receive NFTs.
+pub struct MockNfts;
+
+impl MockNfts {
+	/// Transfer the NFT of `claim` to `dest` without going through the pallet.
+	pub fn set_owner(claim: &[u8], dest: u64) {
+		NftOwners::insert(claim, dest);
+	}
+}
+
+impl ClaimNfts<u64> for MockNfts {
+	fn mint(claim: &[u8], owner: &u64) -> DispatchResult {
+		ensure!(*owner != 9, DispatchError::Other("Cannot hold NFTs"));
+		NftOwners::insert(claim, owner);
+		Ok(())
+	}
+
+	fn transfer(claim: &[u8], dest: &u64) -> DispatchResult {
+		ensure!(*dest != 9, DispatchError::Other("Cannot hold NFTs"));
+		NftOwners::mutate_exists(claim, |owner| owner.as_mut().map(|owner| *owner = *dest));
+		Ok(())
+	}
+
+	fn burn(claim: &[u8]) -> DispatchResult {
+		NftOwners::remove(claim);
+		Ok(())
+	}
+
+	fn owner(claim: &[u8]) -> Option<u64> {
+		NftOwners::get(claim)
+	}
+}
+
+/// A claim change seen by the claim hooks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClaimChange {
+	Created(Vec<u8>, u64),
+	Transferred(Vec<u8>, u64, u64),
+	Revoked(Vec<u8>, u64),
+}
+
+thread_local! {
+	static CLAIM_CHANGES: RefCell<Vec<ClaimChange>> = RefCell::new(Vec::new());
+	static VETO_CLAIM_CHANGES: RefCell<bool> = RefCell::new(false);
+}
+
+/// Records the claim changes, see [`RecordClaimChanges::take`].
+pub struct RecordClaimChanges;
+
+impl RecordClaimChanges {
+	/// The claim changes recorded since the last call.
+	pub fn take() -> Vec<ClaimChange> {
+		CLAIM_CHANGES.with(|changes| changes.take())
+	}
+
+	fn record(change: ClaimChange) -> DispatchResult {
+		CLAIM_CHANGES.with(|changes| changes.borrow_mut().push(change));
+		Ok(())
+	}
+}
+
+impl OnClaimCreated<u64> for RecordClaimChanges {
+	fn on_claim_created(claim: &[u8], owner: &u64) -> DispatchResult {
+		Self::record(ClaimChange::Created(claim.to_vec(), *owner))
+	}
+
+	fn weight() -> Weight {
+		Weight::from_parts(10, 0)
+	}
+}
+
+impl OnClaimTransferred<u64> for RecordClaimChanges {
+	fn on_claim_transferred(claim: &[u8], from: &u64, to: &u64) -> DispatchResult {
+		Self::record(ClaimChange::Transferred(claim.to_vec(), *from, *to))
+	}
+
+	fn weight() -> Weight {
+		Weight::from_parts(10, 0)
+	}
+}
+
+impl OnClaimRevoked<u64> for RecordClaimChanges {
+	fn on_claim_revoked(claim: &[u8], owner: &u64) -> DispatchResult {
+		Self::record(ClaimChange::Revoked(claim.to_vec(), *owner))
+	}
+
+	fn weight() -> Weight {
+		Weight::from_parts(10, 0)
+	}
+}
+
+/// Vetoes every claim change while [`VetoClaimChanges::set`] is on.
+pub struct VetoClaimChanges;
+
+impl VetoClaimChanges {
+	pub fn set(veto: bool) {
+		VETO_CLAIM_CHANGES.with(|v| *v.borrow_mut() = veto);
+	}
+
+	fn check() -> DispatchResult {
+		match VETO_CLAIM_CHANGES.with(|v| *v.borrow()) {
+			true => Err(DispatchError::Other("Vetoed")),
+			false => Ok(()),
+		}
+	}
+}
+
+impl OnClaimCreated<u64> for VetoClaimChanges {
+	fn on_claim_created(_claim: &[u8], _owner: &u64) -> DispatchResult {
+		Self::check()
+	}
+
+	fn weight() -> Weight {
+		Weight::from_parts(5, 0)
+	}
+}
+
+impl OnClaimTransferred<u64> for VetoClaimChanges {
+	fn on_claim_transferred(_claim: &[u8], _from: &u64, _to: &u64) -> DispatchResult {
+		Self::check()
+	}
+
+	fn weight() -> Weight {
+		Weight::from_parts(5, 0)
+	}
+}
+
+impl OnClaimRevoked<u64> for VetoClaimChanges {
+	fn on_claim_revoked(_claim: &[u8], _owner: &u64) -> DispatchResult {
+		Self::check()
+	}
+
+	fn weight() -> Weight {
+		Weight::from_parts(5, 0)
+	}
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: (1..=4).map(|who| (who, 100)).collect() }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = storage.into();
+	// Go past genesis block so events get deposited
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Move to block `n`, running the `on_initialize` of the pallet and the scheduler for every block
+/// on the way.
+pub fn run_to_block(n: u64) {
+	use frame_support::traits::Hooks;
+
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+		PoeModule::on_initialize(System::block_number());
+	}
+}
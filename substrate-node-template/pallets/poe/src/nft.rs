@@ -0,0 +1,119 @@
+//! Representation of claims as NFTs, e.g. in a `pallet_nfts` collection.
+
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure,
+	traits::{
+		tokens::nonfungibles_v2::{Create, Inspect, Mutate, Transfer},
+		Get, TypedGet,
+	},
+};
+use sp_std::marker::PhantomData;
+
+/// The NFTs standing for claims, one per claim and owned by the owner of the claim.
+///
+/// `pallet_poe` mints, transfers and burns the NFT of a claim along with the claim. The owner
+/// of the NFT is the owner of the claim, so transferring the NFT on its own transfers the claim.
+/// Burning the NFT on its own leaves the claim to the account `pallet_poe` last saw owning it.
+pub trait ClaimNfts<AccountId> {
+	/// Create the collection holding the NFTs of claims, called once at genesis before the NFTs
+	/// of the genesis claims are minted.
+	fn create_collection() -> DispatchResult {
+		Ok(())
+	}
+	/// Mint the NFT of `claim` to `owner`.
+	fn mint(claim: &[u8], owner: &AccountId) -> DispatchResult;
+	/// Transfer the NFT of `claim` to `dest`, if it has one.
+	fn transfer(claim: &[u8], dest: &AccountId) -> DispatchResult;
+	/// Burn the NFT of `claim`, if it has one.
+	fn burn(claim: &[u8]) -> DispatchResult;
+	/// The owner of the NFT of `claim`, if it has one.
+	fn owner(claim: &[u8]) -> Option<AccountId>;
+}
+
+/// Claims without NFTs.
+impl<AccountId> ClaimNfts<AccountId> for () {
+	fn mint(_claim: &[u8], _owner: &AccountId) -> DispatchResult {
+		Ok(())
+	}
+
+	fn transfer(_claim: &[u8], _dest: &AccountId) -> DispatchResult {
+		Ok(())
+	}
+
+	fn burn(_claim: &[u8]) -> DispatchResult {
+		Ok(())
+	}
+
+	fn owner(_claim: &[u8]) -> Option<AccountId> {
+		None
+	}
+}
+
+/// Keeps the NFTs of claims in the `Collection` of `Nfts`, usually `pallet_nfts`, as the item
+/// whose id is the blake2-256 hash of the claim.
+///
+/// The collection is created at genesis with `Owner` as its owner and admin, and the
+/// `CollectionConfig`. NFTs are only minted while `Owner` owns the collection, and claims
+/// created without one keep going without one.
+pub struct NftsAdapter<Nfts, Collection, Owner, CollectionConfig, ItemConfig>(
+	PhantomData<(Nfts, Collection, Owner, CollectionConfig, ItemConfig)>,
+);
+
+impl<AccountId, Nfts, Collection, Owner, CollectionConfig, ItemConfig> ClaimNfts<AccountId>
+	for NftsAdapter<Nfts, Collection, Owner, CollectionConfig, ItemConfig>
+where
+	AccountId: PartialEq,
+	Nfts: Inspect<AccountId>
+		+ Create<AccountId, CollectionConfig::Type>
+		+ Mutate<AccountId, ItemConfig>
+		+ Transfer<AccountId>,
+	Nfts::CollectionId: PartialEq,
+	Nfts::ItemId: From<[u8; 32]>,
+	Collection: Get<Nfts::CollectionId>,
+	Owner: Get<AccountId>,
+	CollectionConfig: TypedGet,
+	ItemConfig: Default,
+{
+	fn create_collection() -> DispatchResult {
+		let owner = Owner::get();
+		let collection = Nfts::create_collection(&owner, &owner, &CollectionConfig::get())?;
+		ensure!(
+			collection == Collection::get(),
+			"The NFT collection of claims must be the first one"
+		);
+		Ok(())
+	}
+
+	fn mint(claim: &[u8], owner: &AccountId) -> DispatchResult {
+		let collection = Collection::get();
+		if Nfts::collection_owner(&collection) != Some(Owner::get()) {
+			return Ok(())
+		}
+
+		// 押金由存证所有者支付
+		Nfts::mint_into(&collection, &item_of(claim), owner, &ItemConfig::default(), false)
+	}
+
+	fn transfer(claim: &[u8], dest: &AccountId) -> DispatchResult {
+		match Self::owner(claim) {
+			Some(_) => <Nfts as Transfer<_>>::transfer(&Collection::get(), &item_of(claim), dest),
+			None => Ok(()),
+		}
+	}
+
+	fn burn(claim: &[u8]) -> DispatchResult {
+		match Self::owner(claim) {
+			Some(_) => Nfts::burn(&Collection::get(), &item_of(claim), None),
+			None => Ok(()),
+		}
+	}
+
+	fn owner(claim: &[u8]) -> Option<AccountId> {
+		Nfts::owner(&Collection::get(), &item_of(claim))
+	}
+}
+
+fn item_of<ItemId: From<[u8; 32]>>(claim: &[u8]) -> ItemId {
+	sp_io::hashing::blake2_256(claim).into()
+}
@@ -0,0 +1,129 @@
+//! Off-chain worker checking that the content of claims is still retrievable.
+//!
+//! Every `VerificationInterval` blocks the worker fetches the content of up to
+//! `MaxVerificationsPerRun` claims from `<endpoint>/<claim as hex>`, where `endpoint` is read
+//! from the node's persistent off-chain storage under [`ENDPOINT_KEY`] (it can be set with the
+//! `offchain_localStorageSet` RPC). Content matches a claim when its blake2-256, sha2-256 or
+//! keccak-256 hash equals the claim, the algorithms `poe hash` offers, and content longer than
+//! `max_content_length` never matches. The results are submitted in a single
+//! `submit_availability` transaction signed with a `poe!` key from the node's keystore, whose
+//! account must be an availability signer.
+
+use crate::{Call, Config, Pallet, Proofs};
+use frame_support::traits::Get;
+use frame_system::{
+	offchain::{SendSignedTransaction, Signer},
+	pallet_prelude::BlockNumberFor,
+};
+use sp_core::offchain::StorageKind;
+use sp_runtime::{
+	offchain::{http, Duration},
+	traits::Zero,
+};
+use sp_std::vec::Vec;
+
+/// Persistent off-chain storage key holding the base URL claimed content is fetched from.
+pub const ENDPOINT_KEY: &[u8] = b"poe::endpoint";
+
+/// Persistent off-chain storage key holding the `Proofs` key the next run resumes after.
+const CURSOR_KEY: &[u8] = b"poe::cursor";
+
+/// How long a single content request may take.
+const FETCH_TIMEOUT_MS: u64 = 5_000;
+
+impl<T: Config> Pallet<T> {
+	pub(crate) fn check_availability(now: BlockNumberFor<T>) -> Result<(), &'static str> {
+		let interval = T::VerificationInterval::get();
+		if interval.is_zero() || !(now % interval).is_zero() {
+			return Ok(())
+		}
+
+		let endpoint = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, ENDPOINT_KEY);
+		let endpoint = match endpoint {
+			Some(endpoint) => endpoint,
+			// 没有配置地址时不做检查
+			None => return Ok(()),
+		};
+
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+		if !signer.can_sign() {
+			return Err("no poe! key in the keystore")
+		}
+
+		let max = T::MaxVerificationsPerRun::get() as usize;
+		let cursor = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, CURSOR_KEY);
+		let claims = match cursor {
+			Some(cursor) => Proofs::<T>::iter_keys_from(cursor),
+			None => Proofs::<T>::iter_keys(),
+		};
+		let claims: Vec<_> = claims.take(max).collect();
+
+		// 遍历完所有存证后从头开始
+		match claims.last() {
+			Some(last) if claims.len() == max => sp_io::offchain::local_storage_set(
+				StorageKind::PERSISTENT,
+				CURSOR_KEY,
+				&Proofs::<T>::hashed_key_for(last),
+			),
+			_ => sp_io::offchain::local_storage_clear(StorageKind::PERSISTENT, CURSOR_KEY),
+		}
+
+		if claims.is_empty() {
+			return Ok(())
+		}
+
+		let results: Vec<(Vec<u8>, bool)> = claims
+			.into_iter()
+			.map(|claim| {
+				let available = Self::content_matches(&endpoint, &claim).unwrap_or(false);
+				(claim.into_inner(), available)
+			})
+			.collect();
+
+		match signer
+			.send_signed_transaction(|_| Call::submit_availability { results: results.clone() })
+		{
+			Some((_, Ok(()))) => Ok(()),
+			Some((_, Err(()))) => Err("failed to submit availability attestations"),
+			None => Err("no poe! key in the keystore"),
+		}
+	}
+
+	/// Fetch the content of `claim` from `endpoint` and check that it hashes to the claim with
+	/// one of the supported algorithms.
+	fn content_matches(endpoint: &[u8], claim: &[u8]) -> Result<bool, http::Error> {
+		let url = [endpoint, b"/".as_slice(), to_hex(claim).as_slice()].concat();
+		let url = core::str::from_utf8(&url).map_err(|_| http::Error::Unknown)?;
+
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MS));
+		let pending =
+			http::Request::get(url).deadline(deadline).send().map_err(|_| http::Error::IoError)?;
+		let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+		if response.code != 200 {
+			return Ok(false)
+		}
+
+		// 只读取到长度上限为止
+		let max_length = Self::parameters().max_content_length as usize;
+		let body = response.body().take(max_length.saturating_add(1)).collect::<Vec<u8>>();
+		if body.len() > max_length {
+			return Ok(false)
+		}
+
+		Ok([
+			sp_io::hashing::blake2_256(&body),
+			sp_io::hashing::sha2_256(&body),
+			sp_io::hashing::keccak_256(&body),
+		]
+		.iter()
+		.any(|hash| hash[..] == *claim))
+	}
+}
+
+fn to_hex(bytes: &[u8]) -> Vec<u8> {
+	const DIGITS: &[u8; 16] = b"0123456789abcdef";
+	bytes
+		.iter()
+		.flat_map(|b| [DIGITS[(b >> 4) as usize], DIGITS[(b & 0xf) as usize]])
+		.collect()
+}
@@ -0,0 +1,1325 @@
+use crate::{
+	mock::*, Approvals, Attestation, Attestations, Call, ClaimInspect, ClaimMutate, ClaimNfts,
+	ClaimValidator, Commitments, Creators, Error, Event, ExactLength, ForbiddenPrefixes,
+	LastVerified, License, LicensedClaims, Licenses, Listings, MinLength, Multihash, Operators,
+	Parameters, PoeParameters, Proofs, Revocations, ScheduledAction, ScheduledActions,
+	ScheduledOperation, ValidateClaim, ENDPOINT_KEY,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchInfo, GetDispatchInfo},
+	traits::{ConstU32, Hooks},
+	weights::Weight,
+	BoundedVec,
+};
+use sp_core::{
+	offchain::{
+		testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt,
+	},
+	H256,
+};
+use sp_runtime::{
+	testing::UintAuthorityId,
+	DispatchError,
+	traits::{Dispatchable, SignedExtension},
+	transaction_validity::{InvalidTransaction, TransactionValidity},
+	BuildStorage,
+};
+
+fn bounded(claim: &[u8]) -> BoundedVec<u8, <Test as crate::Config>::MaxClaimLength> {
+	BoundedVec::try_from(claim.to_vec()).unwrap()
+}
+
+#[test]
+fn create_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+		System::assert_last_event(Event::ClaimCreated(1, claim).into());
+	});
+}
+
+#[test]
+fn create_claim_failed_when_claim_already_exist() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(2), claim),
+			Error::<Test>::ProofAlreadyExist
+		);
+	});
+}
+
+#[test]
+fn transfer_claim_keeps_creation_block() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		run_to_block(3);
+
+		assert_noop!(
+			PoeModule::transfer_claim(RuntimeOrigin::signed(2), claim.clone(), 3),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 2));
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((2, 1)));
+	});
+}
+
+#[test]
+fn revoke_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), None);
+		assert_eq!(Revocations::<Test>::get(bounded(&claim)), Some((1, 1)));
+		assert_noop!(
+			PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()),
+			Error::<Test>::ClaimNotExist
+		);
+
+		// 重新创建后不再是已撤销状态
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_eq!(Revocations::<Test>::get(bounded(&claim)), None);
+	});
+}
+
+#[test]
+fn revocations_are_pruned_after_retention() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=3 {
+			assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(who), vec![who as u8]));
+		}
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), vec![1]));
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), vec![2]));
+		assert_noop!(
+			PoeModule::revoke_claim(RuntimeOrigin::signed(3), vec![3]),
+			Error::<Test>::TooManyRevocations
+		);
+
+		// 之后再次撤销的存证按新的撤销区块清理
+		run_to_block(2);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), vec![2]));
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), vec![2]));
+
+		run_to_block(6);
+		assert_eq!(Revocations::<Test>::get(bounded(&[1])), None);
+		assert_eq!(Revocations::<Test>::get(bounded(&[2])), Some((1, 2)));
+		run_to_block(7);
+		assert_eq!(Revocations::<Test>::get(bounded(&[2])), None);
+	});
+}
+
+#[test]
+fn reveal_claim_uses_commitment_block() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let salt = b"salt".to_vec();
+		let commitment = PoeModule::commitment_of(&1, &claim, &salt);
+		assert_ok!(PoeModule::commit_claim(RuntimeOrigin::signed(1), commitment));
+		assert_eq!(Balances::reserved_balance(1), 5);
+		run_to_block(3);
+
+		// A front-runner that learns the claim after the commitment loses it on reveal.
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_ok!(PoeModule::grant_license(
+			RuntimeOrigin::signed(2),
+			claim.clone(),
+			3,
+			H256::repeat_byte(1),
+			None
+		));
+		assert_ok!(PoeModule::add_attester(RuntimeOrigin::root(), 4));
+		assert_ok!(PoeModule::attest_claim(
+			RuntimeOrigin::signed(4),
+			claim.clone(),
+			b"kyc".to_vec(),
+			None
+		));
+		RecordClaimChanges::take();
+		assert_ok!(PoeModule::reveal_claim(RuntimeOrigin::signed(1), claim.clone(), salt));
+
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+		assert_eq!(Commitments::<Test>::get(commitment), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+		System::assert_has_event(Event::ClaimRevoked(2, claim.clone()).into());
+		System::assert_last_event(Event::ClaimCreated(1, claim.clone()).into());
+		// 被接管的存证的许可和背书一并清除
+		assert_eq!(Licenses::<Test>::get(bounded(&claim)).len(), 0);
+		assert!(!LicensedClaims::<Test>::contains_key(3, bounded(&claim)));
+		assert_eq!(Attestations::<Test>::get(bounded(&claim)).len(), 0);
+		assert_eq!(
+			RecordClaimChanges::take(),
+			vec![ClaimChange::Revoked(claim.clone(), 2), ClaimChange::Created(claim.clone(), 1)]
+		);
+	});
+}
+
+#[test]
+fn reveal_claim_does_not_override_older_claim() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let salt = b"salt".to_vec();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(2), claim.clone()));
+		run_to_block(2);
+
+		let commitment = PoeModule::commitment_of(&1, &claim, &salt);
+		assert_ok!(PoeModule::commit_claim(RuntimeOrigin::signed(1), commitment));
+		assert_noop!(
+			PoeModule::reveal_claim(RuntimeOrigin::signed(1), claim, salt),
+			Error::<Test>::ProofAlreadyExist
+		);
+	});
+}
+
+#[test]
+fn reveal_claim_requires_matching_commitment() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let commitment = PoeModule::commitment_of(&1, &claim, b"salt");
+		assert_ok!(PoeModule::commit_claim(RuntimeOrigin::signed(1), commitment));
+
+		assert_noop!(
+			PoeModule::reveal_claim(RuntimeOrigin::signed(1), claim.clone(), b"other".to_vec()),
+			Error::<Test>::CommitmentNotExist
+		);
+		assert_noop!(
+			PoeModule::reveal_claim(RuntimeOrigin::signed(2), claim, b"salt".to_vec()),
+			Error::<Test>::CommitmentNotExist
+		);
+	});
+}
+
+#[test]
+fn unrevealed_commitments_expire() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let salt = b"salt".to_vec();
+		let commitment = PoeModule::commitment_of(&1, &claim, &salt);
+		assert_ok!(PoeModule::commit_claim(RuntimeOrigin::signed(1), commitment));
+
+		assert_eq!(Balances::reserved_balance(1), 5);
+		// 付不起押金的承诺不进入交易池
+		let other = PoeModule::commitment_of(&7, &claim, &salt);
+		assert_eq!(
+			validate(7, Call::commit_claim { commitment: other }),
+			Err(InvalidTransaction::Payment.into())
+		);
+
+		run_to_block(6);
+		assert!(Commitments::<Test>::contains_key(commitment));
+
+		run_to_block(7);
+		assert_eq!(Commitments::<Test>::get(commitment), None);
+		System::assert_last_event(Event::CommitmentExpired(commitment).into());
+		// 押金被没收
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 95);
+		assert_noop!(
+			PoeModule::reveal_claim(RuntimeOrigin::signed(1), claim, salt),
+			Error::<Test>::CommitmentNotExist
+		);
+	});
+}
+
+#[test]
+fn commitments_per_block_are_bounded() {
+	new_test_ext().execute_with(|| {
+		for salt in [b"a", b"b"] {
+			let commitment = PoeModule::commitment_of(&1, &[0], salt);
+			assert_ok!(PoeModule::commit_claim(RuntimeOrigin::signed(1), commitment));
+		}
+
+		let commitment = PoeModule::commitment_of(&1, &[0], b"c");
+		assert_noop!(
+			PoeModule::commit_claim(RuntimeOrigin::signed(1), commitment),
+			Error::<Test>::TooManyCommitments
+		);
+	});
+}
+
+#[test]
+fn claims_per_account_are_rate_limited() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), vec![0]));
+		run_to_block(2);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), vec![1]));
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(1), vec![2]),
+			Error::<Test>::RateLimited
+		);
+
+		// The claim from block 1 leaves the window at block 4.
+		run_to_block(4);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), vec![2]));
+	});
+}
+
+#[test]
+fn claims_per_block_are_rate_limited() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=3 {
+			assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(who), vec![who as u8]));
+		}
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(4), vec![4]),
+			Error::<Test>::RateLimited
+		);
+
+		run_to_block(2);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(4), vec![4]));
+	});
+}
+
+fn validate(who: u64, call: Call<Test>) -> TransactionValidity {
+	ValidateClaim::<Test>::new().validate(&who, &call.into(), &DispatchInfo::default(), 0)
+}
+
+fn invalid(error: Error<Test>) -> TransactionValidity {
+	Err(InvalidTransaction::Custom(error.encode()[0]).into())
+}
+
+#[test]
+fn validate_create_claim_provides_claim_tag() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let valid = validate(1, Call::create_claim { claim: claim.clone() }).unwrap();
+		let other = validate(2, Call::create_claim { claim: claim.clone() }).unwrap();
+		assert_eq!(valid.provides, other.provides);
+
+		assert_eq!(
+			validate(1, Call::create_claim { claim: vec![0; 33] }),
+			invalid(Error::<Test>::ClaimTooLong)
+		);
+
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_eq!(
+			validate(2, Call::create_claim { claim }),
+			invalid(Error::<Test>::ProofAlreadyExist)
+		);
+	});
+}
+
+#[test]
+fn validate_checks_claim_ownership() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_eq!(
+			validate(1, Call::revoke_claim { claim: claim.clone() }),
+			invalid(Error::<Test>::ClaimNotExist)
+		);
+
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(validate(1, Call::transfer_claim { claim: claim.clone(), dest: 2 }));
+		assert_eq!(
+			validate(2, Call::transfer_claim { claim, dest: 3 }),
+			invalid(Error::<Test>::NotClaimOwner)
+		);
+	});
+}
+
+#[test]
+fn validate_rejects_rate_limited_claims() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), vec![0]));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), vec![1]));
+
+		assert_eq!(
+			validate(1, Call::create_claim { claim: vec![2] }),
+			invalid(Error::<Test>::RateLimited)
+		);
+
+		// 区块内存证已满时按区块已满处理，交易留到下一个区块
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(2), vec![3]));
+		assert_eq!(
+			validate(3, Call::create_claim { claim: vec![4] }),
+			Err(InvalidTransaction::ExhaustsResources.into())
+		);
+		run_to_block(2);
+		assert_ok!(validate(3, Call::create_claim { claim: vec![4] }));
+	});
+}
+
+#[test]
+fn validate_defers_revocations_of_a_full_block() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=3 {
+			assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(who), vec![who as u8]));
+		}
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), vec![1]));
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), vec![2]));
+
+		// 区块内撤销已满时交易留到下一个区块，而不是派发时失败
+		assert_eq!(
+			validate(3, Call::revoke_claim { claim: vec![3] }),
+			Err(InvalidTransaction::ExhaustsResources.into())
+		);
+		run_to_block(2);
+		assert_ok!(validate(3, Call::revoke_claim { claim: vec![3] }));
+	});
+}
+
+#[test]
+fn submit_availability_records_verified_block() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		run_to_block(2);
+
+		assert_noop!(
+			PoeModule::add_availability_signer(RuntimeOrigin::signed(1), 9),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PoeModule::submit_availability(RuntimeOrigin::signed(9), vec![(claim.clone(), true)]),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(PoeModule::add_availability_signer(RuntimeOrigin::root(), 9));
+		System::assert_last_event(Event::AvailabilitySignerAdded(9).into());
+		assert_noop!(
+			PoeModule::add_availability_signer(RuntimeOrigin::root(), 9),
+			Error::<Test>::AvailabilitySignerAlreadyExist
+		);
+
+		assert_ok!(PoeModule::submit_availability(
+			RuntimeOrigin::signed(9),
+			vec![(claim.clone(), true), (vec![2], true)]
+		));
+		assert_eq!(LastVerified::<Test>::get(bounded(&claim)), Some(2));
+		System::assert_last_event(Event::AvailabilityAttested(9, claim.clone(), true).into());
+
+		assert_noop!(
+			PoeModule::submit_availability(
+				RuntimeOrigin::signed(9),
+				vec![(claim.clone(), false), (claim.clone(), false), (claim, false)]
+			),
+			Error::<Test>::TooManyAvailabilityResults
+		);
+
+		assert_ok!(PoeModule::remove_availability_signer(RuntimeOrigin::root(), 9));
+		System::assert_last_event(Event::AvailabilitySignerRemoved(9).into());
+		assert_noop!(
+			PoeModule::remove_availability_signer(RuntimeOrigin::root(), 9),
+			Error::<Test>::AvailabilitySignerNotExist
+		);
+		assert_noop!(
+			PoeModule::submit_availability(RuntimeOrigin::signed(9), vec![(claim, true)]),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_availability() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys(vec![9u64]);
+
+	// 内容可以用 `poe hash` 支持的任一算法生成存证
+	let content = b"hello".to_vec();
+	let claim = sp_io::hashing::sha2_256(&content).to_vec();
+	let hex: String = claim.iter().map(|b| format!("{:02x}", b)).collect();
+	state.write().expect_request(PendingRequest {
+		method: "GET".into(),
+		uri: format!("http://localhost/{}", hex),
+		response: Some(content),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let endpoint = b"http://localhost";
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, ENDPOINT_KEY, endpoint);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		PoeModule::offchain_worker(2);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, Some((0, ())));
+		assert_eq!(
+			tx.call,
+			RuntimeCall::PoeModule(Call::submit_availability { results: vec![(claim, true)] })
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_rejects_oversized_content() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys(vec![9u64]);
+
+	// 超过 `max_content_length` 的内容即使哈希匹配也视为不可用
+	let content = vec![0; 17];
+	let claim = sp_io::hashing::blake2_256(&content).to_vec();
+	let hex: String = claim.iter().map(|b| format!("{:02x}", b)).collect();
+	state.write().expect_request(PendingRequest {
+		method: "GET".into(),
+		uri: format!("http://localhost/{}", hex),
+		response: Some(content),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let endpoint = b"http://localhost";
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, ENDPOINT_KEY, endpoint);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		PoeModule::offchain_worker(2);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(
+			tx.call,
+			RuntimeCall::PoeModule(Call::submit_availability { results: vec![(claim, false)] })
+		);
+	});
+}
+
+#[test]
+fn create_claim_with_content_indexes_content() {
+	let content = b"hello".to_vec();
+	let claim = sp_io::hashing::blake2_256(&content).to_vec();
+
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		assert_ok!(PoeModule::create_claim_with_content(RuntimeOrigin::signed(1), content.clone()));
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(2), claim.clone()),
+			Error::<Test>::ProofAlreadyExist
+		);
+		assert_noop!(
+			PoeModule::create_claim_with_content(RuntimeOrigin::signed(2), vec![0; 17]),
+			Error::<Test>::ContentTooLong
+		);
+	});
+	ext.persist_offchain_overlay();
+
+	assert_eq!(ext.offchain_db().get(&PoeModule::content_key(&claim)), Some(content));
+}
+
+#[test]
+fn only_attester_origin_manages_attesters() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::add_attester(RuntimeOrigin::signed(1), 5),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(PoeModule::add_attester(RuntimeOrigin::root(), 5));
+		System::assert_last_event(Event::AttesterAdded(5).into());
+		assert_noop!(
+			PoeModule::add_attester(RuntimeOrigin::root(), 5),
+			Error::<Test>::AttesterAlreadyExist
+		);
+
+		assert_ok!(PoeModule::remove_attester(RuntimeOrigin::root(), 5));
+		assert_noop!(
+			PoeModule::remove_attester(RuntimeOrigin::root(), 5),
+			Error::<Test>::AttesterNotExist
+		);
+	});
+}
+
+#[test]
+fn attest_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::attest_claim(RuntimeOrigin::signed(5), claim.clone(), b"kyc".to_vec(), None),
+			Error::<Test>::NotAttester
+		);
+
+		assert_ok!(PoeModule::add_attester(RuntimeOrigin::root(), 5));
+		assert_ok!(PoeModule::add_attester(RuntimeOrigin::root(), 6));
+		assert_noop!(
+			PoeModule::attest_claim(RuntimeOrigin::signed(5), vec![2], b"kyc".to_vec(), None),
+			Error::<Test>::ClaimNotExist
+		);
+		let kyc = b"kyc".to_vec();
+		assert_noop!(
+			PoeModule::attest_claim(RuntimeOrigin::signed(5), claim.clone(), kyc, Some(1)),
+			Error::<Test>::InvalidExpiry
+		);
+
+		assert_ok!(PoeModule::attest_claim(
+			RuntimeOrigin::signed(5),
+			claim.clone(),
+			b"kyc".to_vec(),
+			None
+		));
+		assert_ok!(PoeModule::attest_claim(
+			RuntimeOrigin::signed(6),
+			claim.clone(),
+			b"notary".to_vec(),
+			Some(3)
+		));
+		System::assert_last_event(
+			Event::ClaimAttested(6, claim.clone(), b"notary".to_vec()).into(),
+		);
+
+		let kyc = Attestation {
+			attester: 5,
+			attestation_type: b"kyc".to_vec().try_into().unwrap(),
+			attested_at: 1,
+			expires_at: None,
+		};
+		assert_eq!(PoeModule::active_attestations(&claim).len(), 2);
+
+		// Expired attestations and those of removed attesters are not returned.
+		run_to_block(3);
+		assert_eq!(PoeModule::active_attestations(&claim), vec![kyc]);
+		assert_ok!(PoeModule::remove_attester(RuntimeOrigin::root(), 5));
+		assert_eq!(PoeModule::active_attestations(&claim), vec![]);
+	});
+}
+
+#[test]
+fn revoke_attestation_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::add_attester(RuntimeOrigin::root(), 5));
+		assert_ok!(PoeModule::attest_claim(
+			RuntimeOrigin::signed(5),
+			claim.clone(),
+			b"kyc".to_vec(),
+			None
+		));
+
+		assert_noop!(
+			PoeModule::revoke_attestation(RuntimeOrigin::signed(6), claim.clone()),
+			Error::<Test>::AttestationNotExist
+		);
+		assert_ok!(PoeModule::revoke_attestation(RuntimeOrigin::signed(5), claim.clone()));
+		assert_eq!(PoeModule::active_attestations(&claim), vec![]);
+	});
+}
+
+fn parameters() -> PoeParameters<u64> {
+	PoeParameters {
+		max_claim_length: 2,
+		reveal_window: 5,
+		max_claims_per_block: 3,
+		max_claims_per_account: 1,
+		rate_limit_window: 3,
+		max_content_length: 16,
+	}
+}
+
+#[test]
+fn set_parameters_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(PoeModule::parameters().max_claim_length, 32);
+
+		assert_noop!(
+			PoeModule::set_parameters(RuntimeOrigin::signed(1), parameters()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PoeModule::set_parameters(
+				RuntimeOrigin::root(),
+				PoeParameters { max_claims_per_block: 4, ..parameters() }
+			),
+			Error::<Test>::ParameterOutOfBounds
+		);
+
+		assert_ok!(PoeModule::set_parameters(RuntimeOrigin::root(), parameters()));
+		assert_eq!(Parameters::<Test>::get(), parameters());
+		System::assert_last_event(Event::ParametersSet(parameters()).into());
+
+		// 新的限制立即生效
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(1), vec![0, 1, 2]),
+			Error::<Test>::ClaimTooLong
+		);
+		assert_eq!(
+			validate(1, Call::create_claim { claim: vec![0, 1, 2] }),
+			invalid(Error::<Test>::ClaimTooLong)
+		);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), vec![0, 1]));
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(1), vec![2]),
+			Error::<Test>::RateLimited
+		);
+	});
+}
+
+#[test]
+fn genesis_sets_parameters_and_claims() {
+	let mut ext: sp_io::TestExternalities = RuntimeGenesisConfig {
+		system: Default::default(),
+		balances: Default::default(),
+		poe_module: crate::GenesisConfig {
+			parameters: Some(parameters()),
+			claims: vec![(vec![0, 1], 1), (vec![2], 2)],
+		},
+	}
+	.build_storage()
+	.unwrap()
+	.into();
+
+	ext.execute_with(|| {
+		assert_eq!(PoeModule::parameters(), parameters());
+		assert_eq!(Proofs::<Test>::get(bounded(&[0, 1])), Some((1, 0)));
+		assert_eq!(Proofs::<Test>::get(bounded(&[2])), Some((2, 0)));
+		assert_eq!(Creators::<Test>::get(bounded(&[2])), Some(2));
+		// 创世存证和其他存证一样有 NFT
+		assert_eq!(MockNfts::owner(&[0, 1]), Some(1));
+		assert_eq!(MockNfts::owner(&[2]), Some(2));
+	});
+}
+
+#[test]
+fn scheduled_transfer_is_dispatched() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		assert_noop!(
+			PoeModule::schedule_transfer(RuntimeOrigin::signed(2), claim.clone(), 3, 3),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_noop!(
+			PoeModule::schedule_transfer(RuntimeOrigin::signed(1), claim.clone(), 2, 1),
+			Error::<Test>::InvalidScheduleTime
+		);
+
+		assert_ok!(PoeModule::schedule_transfer(RuntimeOrigin::signed(1), claim.clone(), 2, 3));
+		let action = ScheduledAction { when: 3, operation: ScheduledOperation::Transfer(2) };
+		assert_eq!(PoeModule::scheduled_action(bounded(&claim)), Some(action.clone()));
+		System::assert_last_event(Event::ActionScheduled(1, claim.clone(), action).into());
+		assert_noop!(
+			PoeModule::schedule_revoke(RuntimeOrigin::signed(1), claim.clone(), 4),
+			Error::<Test>::ActionAlreadyScheduled
+		);
+
+		run_to_block(2);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+
+		run_to_block(3);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((2, 1)));
+		assert_eq!(ScheduledActions::<Test>::get(bounded(&claim)), None);
+	});
+}
+
+#[test]
+fn scheduled_revoke_can_be_canceled() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::cancel_scheduled(RuntimeOrigin::signed(1), claim.clone()),
+			Error::<Test>::NoScheduledAction
+		);
+
+		assert_ok!(PoeModule::schedule_revoke(RuntimeOrigin::signed(1), claim.clone(), 3));
+		assert_noop!(
+			PoeModule::cancel_scheduled(RuntimeOrigin::signed(2), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::cancel_scheduled(RuntimeOrigin::signed(1), claim.clone()));
+		System::assert_last_event(Event::ScheduledActionCanceled(1, claim.clone()).into());
+		assert_eq!(ScheduledActions::<Test>::get(bounded(&claim)), None);
+
+		run_to_block(3);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+
+		// 取消后可以重新安排
+		assert_ok!(PoeModule::schedule_revoke(RuntimeOrigin::signed(1), claim.clone(), 4));
+		run_to_block(4);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), None);
+		assert_eq!(Revocations::<Test>::get(bounded(&claim)), Some((1, 4)));
+	});
+}
+
+#[test]
+fn failed_scheduled_action_can_be_canceled_and_rescheduled() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		// 账户 8 不能持有存证，派发的转移会失败
+		assert_ok!(PoeModule::schedule_transfer(RuntimeOrigin::signed(1), claim.clone(), 8, 3));
+		run_to_block(3);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+		assert!(ScheduledActions::<Test>::contains_key(bounded(&claim)));
+
+		// 失败的记录不会阻止重新安排
+		assert_ok!(PoeModule::schedule_transfer(RuntimeOrigin::signed(1), claim.clone(), 8, 4));
+		run_to_block(4);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+
+		// 也可以取消
+		assert_ok!(PoeModule::cancel_scheduled(RuntimeOrigin::signed(1), claim.clone()));
+		System::assert_last_event(Event::ScheduledActionCanceled(1, claim.clone()).into());
+		assert_eq!(ScheduledActions::<Test>::get(bounded(&claim)), None);
+
+		assert_ok!(PoeModule::schedule_transfer(RuntimeOrigin::signed(1), claim.clone(), 2, 5));
+		run_to_block(5);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((2, 1)));
+	});
+}
+
+#[test]
+fn scheduled_action_is_dropped_when_claim_changes_hands() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::schedule_revoke(RuntimeOrigin::signed(1), claim.clone(), 3));
+
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 2));
+		assert_eq!(ScheduledActions::<Test>::get(bounded(&claim)), None);
+
+		run_to_block(3);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((2, 1)));
+
+		// 新的所有者可以安排自己的操作
+		assert_ok!(PoeModule::schedule_transfer(RuntimeOrigin::signed(2), claim.clone(), 3, 5));
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_eq!(ScheduledActions::<Test>::get(bounded(&claim)), None);
+		run_to_block(5);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), None);
+	});
+}
+
+#[test]
+fn list_and_unlist_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_noop!(
+			PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 10),
+			Error::<Test>::ClaimNotExist
+		);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::list_claim(RuntimeOrigin::signed(2), claim.clone(), 10),
+			Error::<Test>::NotClaimOwner
+		);
+
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 10));
+		System::assert_last_event(Event::ClaimListed(1, claim.clone(), 10).into());
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 20));
+		assert_eq!(Listings::<Test>::get(bounded(&claim)), Some((1, 20)));
+
+		assert_noop!(
+			PoeModule::unlist_claim(RuntimeOrigin::signed(2), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::unlist_claim(RuntimeOrigin::signed(1), claim.clone()));
+		System::assert_last_event(Event::ClaimUnlisted(1, claim.clone()).into());
+		assert_noop!(
+			PoeModule::unlist_claim(RuntimeOrigin::signed(1), claim.clone()),
+			Error::<Test>::NotListed
+		);
+		assert_noop!(
+			PoeModule::buy_claim(RuntimeOrigin::signed(2), claim, 20),
+			Error::<Test>::NotListed
+		);
+	});
+}
+
+#[test]
+fn buy_claim_pays_seller_and_creator() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 50));
+
+		assert_noop!(
+			PoeModule::buy_claim(RuntimeOrigin::signed(1), claim.clone(), 50),
+			Error::<Test>::CannotBuyOwnClaim
+		);
+		assert_noop!(
+			PoeModule::buy_claim(RuntimeOrigin::signed(2), claim.clone(), 49),
+			Error::<Test>::PriceTooHigh
+		);
+
+		// 创建者自己出售时不收版税
+		assert_ok!(PoeModule::buy_claim(RuntimeOrigin::signed(2), claim.clone(), 50));
+		System::assert_last_event(Event::ClaimSold(1, 2, claim.clone(), 50).into());
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((2, 1)));
+		assert_eq!(Listings::<Test>::get(bounded(&claim)), None);
+		assert_eq!((Balances::free_balance(1), Balances::free_balance(2)), (150, 50));
+
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(2), claim.clone(), 40));
+		assert_ok!(PoeModule::buy_claim(RuntimeOrigin::signed(3), claim.clone(), 40));
+		System::assert_has_event(Event::RoyaltyPaid(1, claim.clone(), 4).into());
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((3, 1)));
+		assert_eq!(Balances::free_balance(1), 154);
+		assert_eq!(Balances::free_balance(2), 86);
+		assert_eq!(Balances::free_balance(3), 60);
+	});
+}
+
+#[test]
+fn buy_claim_fails_without_funds() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 100));
+
+		assert!(PoeModule::buy_claim(RuntimeOrigin::signed(2), claim.clone(), 100).is_err());
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+		assert_eq!(Balances::free_balance(2), 100);
+	});
+}
+
+#[test]
+fn listing_is_dropped_when_claim_changes_hands() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 10));
+
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 2));
+		System::assert_last_event(Event::ClaimTransfered(1, 2, claim.clone()).into());
+		assert_eq!(Listings::<Test>::get(bounded(&claim)), None);
+		assert_noop!(
+			PoeModule::buy_claim(RuntimeOrigin::signed(3), claim.clone(), 10),
+			Error::<Test>::NotListed
+		);
+
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(2), claim.clone(), 10));
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_eq!(Listings::<Test>::get(bounded(&claim)), None);
+		assert_eq!(Creators::<Test>::get(bounded(&claim)), None);
+	});
+}
+
+#[test]
+fn grant_and_revoke_license_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let terms = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		assert_noop!(
+			PoeModule::grant_license(RuntimeOrigin::signed(2), claim.clone(), 3, terms, None),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_noop!(
+			PoeModule::grant_license(RuntimeOrigin::signed(1), claim.clone(), 3, terms, Some(1)),
+			Error::<Test>::InvalidExpiry
+		);
+
+		assert_ok!(PoeModule::grant_license(
+			RuntimeOrigin::signed(1),
+			claim.clone(),
+			2,
+			terms,
+			None
+		));
+		System::assert_last_event(Event::LicenseGranted(1, claim.clone(), 2, None).into());
+		assert_ok!(PoeModule::grant_license(
+			RuntimeOrigin::signed(1),
+			claim.clone(),
+			3,
+			terms,
+			None
+		));
+		assert_noop!(
+			PoeModule::grant_license(RuntimeOrigin::signed(1), claim.clone(), 4, terms, None),
+			Error::<Test>::TooManyLicenses
+		);
+
+		let license = License { licensee: 2, terms_hash: terms, granted_at: 1, expires_at: None };
+		assert_eq!(PoeModule::active_licenses(&claim).len(), 2);
+		assert_eq!(PoeModule::licenses_of(&2), vec![(claim.clone(), license)]);
+
+		assert_noop!(
+			PoeModule::revoke_license(RuntimeOrigin::signed(2), claim.clone(), 2),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::revoke_license(RuntimeOrigin::signed(1), claim.clone(), 2));
+		System::assert_last_event(Event::LicenseRevoked(1, claim.clone(), 2).into());
+		assert_noop!(
+			PoeModule::revoke_license(RuntimeOrigin::signed(1), claim.clone(), 2),
+			Error::<Test>::LicenseNotExist
+		);
+		assert_eq!(PoeModule::licenses_of(&2), vec![]);
+		assert_eq!(LicensedClaims::<Test>::get(2, bounded(&claim)), None);
+
+		// 许可不随存证转移失效，但随存证撤销而删除
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 2));
+		assert_eq!(PoeModule::active_licenses(&claim).len(), 1);
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_eq!(Licenses::<Test>::get(bounded(&claim)).len(), 0);
+		assert_eq!(PoeModule::licenses_of(&3), vec![]);
+		assert_eq!(LicensedClaims::<Test>::get(3, bounded(&claim)), None);
+	});
+}
+
+#[test]
+fn license_expires() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let terms = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::grant_license(
+			RuntimeOrigin::signed(1),
+			claim.clone(),
+			2,
+			terms,
+			Some(3)
+		));
+		assert_ok!(PoeModule::grant_license(
+			RuntimeOrigin::signed(1),
+			claim.clone(),
+			3,
+			terms,
+			Some(3)
+		));
+		// 重新授予的许可按新的期限到期
+		assert_ok!(PoeModule::grant_license(
+			RuntimeOrigin::signed(1),
+			claim.clone(),
+			3,
+			terms,
+			Some(4)
+		));
+
+		run_to_block(2);
+		assert_eq!(PoeModule::active_licenses(&claim).len(), 2);
+
+		run_to_block(3);
+		System::assert_last_event(Event::LicenseExpired(claim.clone(), 2).into());
+		assert_eq!(PoeModule::licenses_of(&2), vec![]);
+		assert_eq!(LicensedClaims::<Test>::get(2, bounded(&claim)), None);
+		assert_eq!(PoeModule::licenses_of(&3).len(), 1);
+
+		run_to_block(4);
+		assert_eq!(Licenses::<Test>::get(bounded(&claim)).len(), 0);
+		assert_eq!(PoeModule::licenses_of(&3), vec![]);
+	});
+}
+
+#[test]
+fn claim_nft_follows_claim() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_eq!(MockNfts::owner(&claim), Some(1));
+
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 2));
+		assert_eq!(MockNfts::owner(&claim), Some(2));
+
+		// NFT 转移失败时存证也不转移
+		assert!(PoeModule::transfer_claim(RuntimeOrigin::signed(2), claim.clone(), 9).is_err());
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((2, 1)));
+
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_eq!(MockNfts::owner(&claim), None);
+	});
+}
+
+#[test]
+fn claim_follows_claim_nft() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 10));
+
+		MockNfts::set_owner(&claim, 2);
+		assert_eq!(
+			validate(1, Call::revoke_claim { claim: claim.clone() }),
+			invalid(Error::<Test>::NotClaimOwner)
+		);
+		assert_ok!(validate(2, Call::revoke_claim { claim: claim.clone() }));
+
+		assert_ok!(PoeModule::sync_claim_owner(RuntimeOrigin::signed(3), claim.clone()));
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((2, 1)));
+		assert_eq!(Listings::<Test>::get(bounded(&claim)), None);
+		System::assert_last_event(Event::ClaimTransfered(1, 2, claim.clone()).into());
+
+		// 其他调用也会先同步所有者
+		MockNfts::set_owner(&claim, 3);
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(3), claim.clone(), 4));
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((4, 1)));
+		assert_eq!(MockNfts::owner(&claim), Some(4));
+	});
+}
+
+#[test]
+fn claimants_must_pass_the_filter() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(8), claim.clone()),
+			Error::<Test>::IdentityRequired
+		);
+		assert_eq!(
+			validate(8, Call::create_claim { claim: claim.clone() }),
+			invalid(Error::<Test>::IdentityRequired)
+		);
+
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_eq!(
+			validate(1, Call::transfer_claim { claim: claim.clone(), dest: 8 }),
+			invalid(Error::<Test>::IdentityRequired)
+		);
+		assert_noop!(
+			PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 8),
+			Error::<Test>::IdentityRequired
+		);
+
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 10));
+		assert_noop!(
+			PoeModule::buy_claim(RuntimeOrigin::signed(8), claim.clone(), 10),
+			Error::<Test>::IdentityRequired
+		);
+
+		// 单独转移给未通过身份验证账户的 NFT 会被退回
+		MockNfts::set_owner(&claim, 8);
+		assert_eq!(<PoeModule as ClaimInspect<_, _>>::owner_of(&claim), Some(1));
+		assert_ok!(PoeModule::sync_claim_owner(RuntimeOrigin::signed(2), claim.clone()));
+		System::assert_last_event(Event::ClaimNftReturned(8, 1, claim.clone()).into());
+		assert_eq!(MockNfts::owner(&claim), Some(1));
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+	});
+}
+
+#[test]
+fn claim_traits_work() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert!(!<PoeModule as ClaimInspect<_, _>>::exists(&claim));
+		assert_eq!(<PoeModule as ClaimInspect<_, _>>::owner_of(&claim), None);
+
+		assert_ok!(<PoeModule as ClaimMutate<_, _>>::create(&1, &claim));
+		System::assert_last_event(Event::ClaimCreated(1, claim.clone()).into());
+		assert_noop!(
+			<PoeModule as ClaimMutate<_, _>>::create(&2, &claim),
+			Error::<Test>::ProofAlreadyExist
+		);
+		assert!(<PoeModule as ClaimInspect<_, _>>::exists(&claim));
+		assert_eq!(<PoeModule as ClaimInspect<_, _>>::created_at(&claim), Some(1));
+
+		run_to_block(2);
+		assert_ok!(<PoeModule as ClaimMutate<_, _>>::transfer(&claim, &2));
+		assert_eq!(<PoeModule as ClaimInspect<_, _>>::owner_of(&claim), Some(2));
+		assert_eq!(<PoeModule as ClaimInspect<_, _>>::created_at(&claim), Some(1));
+		assert_noop!(
+			<PoeModule as ClaimMutate<_, _>>::transfer(&claim, &8),
+			Error::<Test>::IdentityRequired
+		);
+		assert_noop!(
+			<PoeModule as ClaimMutate<_, _>>::transfer(&[2], &1),
+			Error::<Test>::ClaimNotExist
+		);
+
+		// 所有者以 NFT 为准
+		MockNfts::set_owner(&claim, 3);
+		assert_eq!(<PoeModule as ClaimInspect<_, _>>::owner_of(&claim), Some(3));
+	});
+}
+
+#[test]
+fn claim_hooks_are_called_and_can_veto() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let create = RuntimeCall::PoeModule(Call::create_claim { claim: claim.clone() });
+		let transfer =
+			RuntimeCall::PoeModule(Call::transfer_claim { claim: claim.clone(), dest: 2 });
+		let revoke = RuntimeCall::PoeModule(Call::revoke_claim { claim: claim.clone() });
+		assert_eq!(create.get_dispatch_info().weight, Weight::from_parts(15, 0));
+		assert_eq!(transfer.get_dispatch_info().weight, Weight::from_parts(30, 0));
+		assert_eq!(revoke.get_dispatch_info().weight, Weight::from_parts(30, 0));
+
+		// 被否决的操作整体回滚
+		VetoClaimChanges::set(true);
+		assert_noop!(
+			create.clone().dispatch(RuntimeOrigin::signed(1)),
+			DispatchError::Other("Vetoed")
+		);
+		VetoClaimChanges::set(false);
+		assert_ok!(create.dispatch(RuntimeOrigin::signed(1)));
+		assert_eq!(RecordClaimChanges::take(), vec![ClaimChange::Created(claim.clone(), 1)]);
+
+		VetoClaimChanges::set(true);
+		assert_noop!(
+			transfer.clone().dispatch(RuntimeOrigin::signed(1)),
+			DispatchError::Other("Vetoed")
+		);
+		assert_noop!(
+			revoke.clone().dispatch(RuntimeOrigin::signed(1)),
+			DispatchError::Other("Vetoed")
+		);
+		VetoClaimChanges::set(false);
+		assert_ok!(transfer.dispatch(RuntimeOrigin::signed(1)));
+		assert_eq!(RecordClaimChanges::take(), vec![ClaimChange::Transferred(claim.clone(), 1, 2)]);
+
+		// 单独转移 NFT 也会调用钩子
+		MockNfts::set_owner(&claim, 3);
+		assert_ok!(revoke.dispatch(RuntimeOrigin::signed(3)));
+		assert_eq!(
+			RecordClaimChanges::take(),
+			vec![
+				ClaimChange::Transferred(claim.clone(), 2, 3),
+				ClaimChange::Revoked(claim.clone(), 3)
+			]
+		);
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), None);
+	});
+}
+
+#[test]
+fn vetoed_nft_transfer_returns_the_nft() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		RecordClaimChanges::take();
+
+		// 被否决的 NFT 转移退回原所有者，不会让之后的调用都失败
+		VetoClaimChanges::set(true);
+		MockNfts::set_owner(&claim, 3);
+		assert_ok!(PoeModule::list_claim(RuntimeOrigin::signed(1), claim.clone(), 10));
+		System::assert_has_event(Event::ClaimNftReturned(3, 1, claim.clone()).into());
+		assert_eq!(MockNfts::owner(&claim), Some(1));
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((1, 1)));
+		assert_eq!(Listings::<Test>::get(bounded(&claim)), Some((1, 10)));
+		VetoClaimChanges::set(false);
+		assert_eq!(RecordClaimChanges::take(), vec![]);
+
+		MockNfts::set_owner(&claim, 3);
+		assert_ok!(PoeModule::sync_claim_owner(RuntimeOrigin::signed(2), claim.clone()));
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((3, 1)));
+		assert_eq!(RecordClaimChanges::take(), vec![ClaimChange::Transferred(claim, 1, 3)]);
+	});
+}
+
+#[test]
+fn claims_must_pass_the_validator() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(1), vec![]),
+			Error::<Test>::InvalidClaimFormat
+		);
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(1), vec![0xff, 1]),
+			Error::<Test>::InvalidClaimFormat
+		);
+		assert_eq!(
+			validate(1, Call::create_claim { claim: vec![0xff] }),
+			invalid(Error::<Test>::InvalidClaimFormat)
+		);
+		assert_noop!(
+			<PoeModule as ClaimMutate<_, _>>::create(&1, &[0xff]),
+			Error::<Test>::InvalidClaimFormat
+		);
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), vec![1, 0xff]));
+	});
+}
+
+#[test]
+fn built_in_claim_validators_work() {
+	assert!(ExactLength::<ConstU32<2>>::validate(&[0, 1]));
+	assert!(!ExactLength::<ConstU32<2>>::validate(&[0]));
+	assert!(!ExactLength::<ConstU32<2>>::validate(&[0, 1, 2]));
+
+	assert!(MinLength::<ConstU32<2>>::validate(&[0, 1, 2]));
+	assert!(!MinLength::<ConstU32<2>>::validate(&[0]));
+
+	assert!(ForbiddenPrefixes::<ForbiddenClaimPrefixes>::validate(&[0, 0xff]));
+	assert!(!ForbiddenPrefixes::<ForbiddenClaimPrefixes>::validate(&[0xff, 0]));
+
+	// sha2-256 (0x12) 的 32 字节摘要
+	let mut sha2_256 = vec![0x12, 0x20];
+	sha2_256.extend([7; 32]);
+	assert!(Multihash::validate(&sha2_256));
+	assert!(!Multihash::validate(&sha2_256[..33]));
+	assert!(!Multihash::validate(&[0x12, 0x00]));
+	assert!(!Multihash::validate(&[0x12]));
+	assert!(!Multihash::validate(&[]));
+	// blake2b-256 (0xb220) 的代码占两个字节
+	assert!(Multihash::validate(&[0xa0, 0xe4, 0x02, 0x01, 7]));
+	assert!(!Multihash::validate(&[0x92, 0x00, 0x01, 7]));
+
+	assert!(<()>::validate(&[]));
+	assert!(<(MinLength<ConstU32<1>>, Multihash)>::validate(&[0x12, 0x01, 7]));
+	assert!(!<(MinLength<ConstU32<4>>, Multihash)>::validate(&[0x12, 0x01, 7]));
+}
+
+#[test]
+fn approvals_and_operators_work() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::transfer_claim(RuntimeOrigin::signed(2), claim.clone(), 3),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_noop!(
+			PoeModule::approve(RuntimeOrigin::signed(1), claim.clone(), 1),
+			Error::<Test>::ApproveToOwner
+		);
+		assert_noop!(
+			PoeModule::approve(RuntimeOrigin::signed(3), claim.clone(), 2),
+			Error::<Test>::NotClaimOwner
+		);
+
+		assert_ok!(PoeModule::approve(RuntimeOrigin::signed(1), claim.clone(), 2));
+		System::assert_last_event(Event::ApprovalSet(1, claim.clone(), 2).into());
+		assert_eq!(Approvals::<Test>::get(bounded(&claim)), Some(2));
+		assert!(validate(2, Call::transfer_claim { claim: claim.clone(), dest: 3 }).is_ok());
+
+		// 转移后授权失效
+		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(2), claim.clone(), 3));
+		System::assert_last_event(Event::ClaimTransfered(1, 3, claim.clone()).into());
+		assert_eq!(Proofs::<Test>::get(bounded(&claim)), Some((3, 1)));
+		assert_eq!(Approvals::<Test>::get(bounded(&claim)), None);
+		assert_noop!(
+			PoeModule::transfer_claim(RuntimeOrigin::signed(2), claim.clone(), 2),
+			Error::<Test>::NotClaimOwner
+		);
+
+		assert_ok!(PoeModule::approve(RuntimeOrigin::signed(3), claim.clone(), 2));
+		assert_ok!(PoeModule::cancel_approval(RuntimeOrigin::signed(3), claim.clone()));
+		System::assert_last_event(Event::ApprovalCanceled(3, claim.clone()).into());
+		assert_noop!(
+			PoeModule::cancel_approval(RuntimeOrigin::signed(3), claim.clone()),
+			Error::<Test>::NoApproval
+		);
+
+		assert_noop!(
+			PoeModule::set_approval_for_all(RuntimeOrigin::signed(3), 3, true),
+			Error::<Test>::ApproveToOwner
+		);
+		assert_ok!(PoeModule::set_approval_for_all(RuntimeOrigin::signed(3), 4, true));
+		System::assert_last_event(Event::ApprovalForAllSet(3, 4, true).into());
+		assert_ok!(PoeModule::approve(RuntimeOrigin::signed(4), claim.clone(), 2));
+		System::assert_last_event(Event::ApprovalSet(3, claim.clone(), 2).into());
+
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(4), claim.clone()));
+		System::assert_last_event(Event::ClaimRevoked(3, claim.clone()).into());
+		assert_eq!(Revocations::<Test>::get(bounded(&claim)), Some((3, 1)));
+		assert_eq!(Approvals::<Test>::get(bounded(&claim)), None);
+
+		assert_ok!(PoeModule::set_approval_for_all(RuntimeOrigin::signed(3), 4, false));
+		System::assert_last_event(Event::ApprovalForAllSet(3, 4, false).into());
+		assert!(!Operators::<Test>::contains_key(3, 4));
+	});
+}
@@ -0,0 +1,140 @@
+//! Access to claims for other pallets of the runtime, independent of the storage layout.
+
+use crate::{Config, Error, Pallet, Proofs};
+use frame_support::{dispatch::DispatchResult, ensure, weights::Weight};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+/// Read access to the claims of `pallet_poe`.
+pub trait ClaimInspect<AccountId, BlockNumber> {
+	/// The owner of `claim`, if it exists.
+	fn owner_of(claim: &[u8]) -> Option<AccountId>;
+
+	/// Whether `claim` exists.
+	fn exists(claim: &[u8]) -> bool {
+		Self::owner_of(claim).is_some()
+	}
+
+	/// The block `claim` was created in, if it exists.
+	fn created_at(claim: &[u8]) -> Option<BlockNumber>;
+}
+
+/// Write access to the claims of `pallet_poe`, for pallets acting on behalf of their users.
+///
+/// The caller is responsible for checking that the account it acts for may do so. The rate limits
+/// of `create_claim` do not apply, everything else does.
+pub trait ClaimMutate<AccountId, BlockNumber>: ClaimInspect<AccountId, BlockNumber> {
+	/// Create `claim`, owned by `owner`.
+	fn create(owner: &AccountId, claim: &[u8]) -> DispatchResult;
+
+	/// Transfer `claim` from its owner to `dest`.
+	fn transfer(claim: &[u8], dest: &AccountId) -> DispatchResult;
+}
+
+/// Called by `pallet_poe` whenever a claim is created, by any call or through [`ClaimMutate`].
+///
+/// The hook runs after the claim is stored, and returning an error vetoes the creation. Tuples of
+/// hooks call each of them in order, `()` does nothing.
+pub trait OnClaimCreated<AccountId> {
+	/// `owner` created `claim`.
+	fn on_claim_created(claim: &[u8], owner: &AccountId) -> DispatchResult;
+
+	/// The weight of [`Self::on_claim_created`], charged by the calls that may create a claim.
+	fn weight() -> Weight;
+}
+
+/// Called by `pallet_poe` whenever a claim changes hands, including sales, scheduled transfers and
+/// transfers of the NFT of the claim.
+///
+/// The hook runs after the new owner is stored, and returning an error vetoes the transfer. A
+/// vetoed transfer of the NFT on its own sends the NFT back to the owner.
+pub trait OnClaimTransferred<AccountId> {
+	/// `claim` was transferred from `from` to `to`.
+	fn on_claim_transferred(claim: &[u8], from: &AccountId, to: &AccountId) -> DispatchResult;
+
+	/// The weight of [`Self::on_claim_transferred`], charged by the calls that may transfer a
+	/// claim.
+	fn weight() -> Weight;
+}
+
+/// Called by `pallet_poe` whenever a claim is revoked, including claims taken over by
+/// `reveal_claim`.
+///
+/// The hook runs after the claim is removed, and returning an error vetoes the revocation.
+pub trait OnClaimRevoked<AccountId> {
+	/// `owner` revoked `claim`.
+	fn on_claim_revoked(claim: &[u8], owner: &AccountId) -> DispatchResult;
+
+	/// The weight of [`Self::on_claim_revoked`], charged by `revoke_claim` and `reveal_claim`.
+	fn weight() -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId> OnClaimCreated<AccountId> for Tuple {
+	fn on_claim_created(claim: &[u8], owner: &AccountId) -> DispatchResult {
+		for_tuples!( #( Tuple::on_claim_created(claim, owner)?; )* );
+		Ok(())
+	}
+
+	fn weight() -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+		weight
+	}
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId> OnClaimTransferred<AccountId> for Tuple {
+	fn on_claim_transferred(claim: &[u8], from: &AccountId, to: &AccountId) -> DispatchResult {
+		for_tuples!( #( Tuple::on_claim_transferred(claim, from, to)?; )* );
+		Ok(())
+	}
+
+	fn weight() -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+		weight
+	}
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId> OnClaimRevoked<AccountId> for Tuple {
+	fn on_claim_revoked(claim: &[u8], owner: &AccountId) -> DispatchResult {
+		for_tuples!( #( Tuple::on_claim_revoked(claim, owner)?; )* );
+		Ok(())
+	}
+
+	fn weight() -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+		weight
+	}
+}
+
+impl<T: Config> ClaimInspect<T::AccountId, BlockNumberFor<T>> for Pallet<T> {
+	fn owner_of(claim: &[u8]) -> Option<T::AccountId> {
+		let bounded_claim = claim.to_vec().try_into().ok()?;
+		Self::current_owner(&bounded_claim).map(|(owner, _)| owner)
+	}
+
+	fn created_at(claim: &[u8]) -> Option<BlockNumberFor<T>> {
+		let bounded_claim = claim.to_vec().try_into().ok()?;
+		Proofs::<T>::get(&bounded_claim).map(|(_, created_at)| created_at)
+	}
+}
+
+impl<T: Config> ClaimMutate<T::AccountId, BlockNumberFor<T>> for Pallet<T> {
+	fn create(owner: &T::AccountId, claim: &[u8]) -> DispatchResult {
+		let bounded_claim = Self::new_claim(claim.to_vec())?;
+		ensure!(!Proofs::<T>::contains_key(&bounded_claim), Error::<T>::ProofAlreadyExist);
+
+		let now = frame_system::Pallet::<T>::block_number();
+		Self::do_create_claim(owner.clone(), bounded_claim, now)
+	}
+
+	fn transfer(claim: &[u8], dest: &T::AccountId) -> DispatchResult {
+		let bounded_claim = claim.to_vec().try_into().map_err(|_| Error::<T>::ClaimTooLong)?;
+		let (owner, created_at) = Self::sync_owner(&bounded_claim)?;
+
+		Self::do_transfer_claim(&bounded_claim, owner, dest.clone(), created_at)
+	}
+}
@@ -0,0 +1,136 @@
+//! Types used by the proof of existence pallet.
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+
+/// An endorsement of a claim by a registered attester.
+#[derive(
+	Encode,
+	Decode,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+	Serialize,
+	Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestation<AccountId, BlockNumber, AttestationType> {
+	/// The attester vouching for the claim.
+	pub attester: AccountId,
+	/// What the attester vouches for, e.g. `b"identity"` or `b"notarized"`.
+	pub attestation_type: AttestationType,
+	/// The block the attestation was made in.
+	pub attested_at: BlockNumber,
+	/// The block from which the attestation no longer holds, if any.
+	pub expires_at: Option<BlockNumber>,
+}
+
+impl<AccountId, BlockNumber: PartialOrd, AttestationType>
+	Attestation<AccountId, BlockNumber, AttestationType>
+{
+	/// Whether the attestation still holds at block `now`.
+	pub fn is_active(&self, now: &BlockNumber) -> bool {
+		self.expires_at.as_ref().map_or(true, |expires_at| now < expires_at)
+	}
+}
+
+/// Usage rights granted on a claim by its owner, without transferring the claim.
+#[derive(
+	Encode,
+	Decode,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+	Serialize,
+	Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct License<AccountId, Hash, BlockNumber> {
+	/// The account granted the licence.
+	pub licensee: AccountId,
+	/// The hash of the licence terms, which are kept off-chain.
+	pub terms_hash: Hash,
+	/// The block the licence was granted in.
+	pub granted_at: BlockNumber,
+	/// The block from which the licence no longer holds, if any.
+	pub expires_at: Option<BlockNumber>,
+}
+
+impl<AccountId, Hash, BlockNumber: PartialOrd> License<AccountId, Hash, BlockNumber> {
+	/// Whether the licence still holds at block `now`.
+	pub fn is_active(&self, now: &BlockNumber) -> bool {
+		self.expires_at.as_ref().map_or(true, |expires_at| now < expires_at)
+	}
+}
+
+/// Tunables of the pallet that governance can change with `set_parameters`, each capped by the
+/// matching constant of the pallet's configuration.
+#[derive(
+	Encode,
+	Decode,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+	Serialize,
+	Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct PoeParameters<BlockNumber> {
+	/// The maximum length of a new claim, at most `MaxClaimLength`.
+	pub max_claim_length: u32,
+	/// Number of blocks after `commit_claim` during which the claim can be revealed, at most
+	/// `RevealWindow`.
+	pub reveal_window: BlockNumber,
+	/// The maximum number of claims created in a single block, at most `MaxClaimsPerBlock`.
+	pub max_claims_per_block: u32,
+	/// The maximum number of claims a single account can create within `rate_limit_window`, at
+	/// most `MaxClaimsPerAccount`.
+	pub max_claims_per_account: u32,
+	/// The number of blocks over which `max_claims_per_account` is enforced, at most
+	/// `RateLimitWindow`.
+	pub rate_limit_window: BlockNumber,
+	/// The maximum length of content stored with `create_claim_with_content`, at most
+	/// `MaxContentLength`.
+	pub max_content_length: u32,
+}
+
+impl<BlockNumber: PartialOrd> PoeParameters<BlockNumber> {
+	/// Whether every parameter is within the matching parameter of `bounds`.
+	pub fn is_within(&self, bounds: &Self) -> bool {
+		self.max_claim_length <= bounds.max_claim_length &&
+			self.reveal_window <= bounds.reveal_window &&
+			self.max_claims_per_block <= bounds.max_claims_per_block &&
+			self.max_claims_per_account <= bounds.max_claims_per_account &&
+			self.rate_limit_window <= bounds.rate_limit_window &&
+			self.max_content_length <= bounds.max_content_length
+	}
+}
+
+/// What a scheduled action does to its claim.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum ScheduledOperation<AccountId> {
+	/// Transfer the claim to the account.
+	Transfer(AccountId),
+	/// Revoke the claim.
+	Revoke,
+}
+
+/// A `transfer_claim` or `revoke_claim` the owner of a claim scheduled for a later block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ScheduledAction<AccountId, BlockNumber> {
+	/// The block the action is dispatched in.
+	pub when: BlockNumber,
+	/// The action.
+	pub operation: ScheduledOperation<AccountId>,
+}
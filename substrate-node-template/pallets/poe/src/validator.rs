@@ -0,0 +1,78 @@
+//! Format rules for new claims, on top of the `max_claim_length` bound.
+
+use frame_support::traits::Get;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Decides whether a new claim is well-formed, failing its creation with `InvalidClaimFormat`
+/// otherwise.
+///
+/// Tuples of validators require each of them to accept the claim, `()` accepts every claim.
+pub trait ClaimValidator {
+	/// Whether `claim` is well-formed.
+	fn validate(claim: &[u8]) -> bool;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl ClaimValidator for Tuple {
+	fn validate(claim: &[u8]) -> bool {
+		for_tuples!( #( if !Tuple::validate(claim) { return false } )* );
+		true
+	}
+}
+
+/// Claims of exactly `Length` bytes, e.g. 32 for raw blake2-256 or sha2-256 digests.
+pub struct ExactLength<Length>(PhantomData<Length>);
+
+impl<Length: Get<u32>> ClaimValidator for ExactLength<Length> {
+	fn validate(claim: &[u8]) -> bool {
+		claim.len() as u32 == Length::get()
+	}
+}
+
+/// Claims of at least `Length` bytes.
+pub struct MinLength<Length>(PhantomData<Length>);
+
+impl<Length: Get<u32>> ClaimValidator for MinLength<Length> {
+	fn validate(claim: &[u8]) -> bool {
+		claim.len() as u32 >= Length::get()
+	}
+}
+
+/// Claims not starting with any of the `Prefixes`, e.g. to reserve them for the runtime.
+pub struct ForbiddenPrefixes<Prefixes>(PhantomData<Prefixes>);
+
+impl<Prefixes: Get<Vec<Vec<u8>>>> ClaimValidator for ForbiddenPrefixes<Prefixes> {
+	fn validate(claim: &[u8]) -> bool {
+		!Prefixes::get().iter().any(|prefix| claim.starts_with(prefix))
+	}
+}
+
+/// Claims that are multihashes: the varint code of the hash function, the varint length of the
+/// digest and the digest itself.
+///
+/// Only the structure is checked, any hash function code is accepted.
+pub struct Multihash;
+
+impl ClaimValidator for Multihash {
+	fn validate(claim: &[u8]) -> bool {
+		let Some((_code, rest)) = read_varint(claim) else { return false };
+		let Some((length, digest)) = read_varint(rest) else { return false };
+		length > 0 && digest.len() as u64 == length
+	}
+}
+
+/// Read an unsigned varint as used by multiformats, at most 9 bytes and minimally encoded.
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+	let mut value = 0u64;
+	for (i, byte) in bytes.iter().enumerate().take(9) {
+		value |= u64::from(byte & 0x7f) << (7 * i);
+		if byte & 0x80 == 0 {
+			// 非最短编码（以 0x00 结尾的多字节 varint）无效
+			if i > 0 && *byte == 0 {
+				return None
+			}
+			return Some((value, &bytes[i + 1..]))
+		}
+	}
+	None
+}
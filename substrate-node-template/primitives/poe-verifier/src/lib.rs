@@ -0,0 +1,418 @@
+//! Offline verification of proof of existence claims.
+//!
+//! The node's `poe_getClaimProof` RPC returns a [`ClaimProof`]: the header of a finalized block,
+//! a GRANDPA finality proof for it and a storage proof of the claim's entries in the PoE pallet's
+//! `Proofs` and `Revocations` maps, the claim's NFT and the identity of the NFT's holder.
+//! [`verify_claim`] checks such a bundle against a GRANDPA authority set the caller trusts, so a
+//! claim can be verified without trusting, or even talking to, any node. The owner is resolved
+//! as the runtime does: the holder of the claim's NFT owns the claim if registrar
+//! [`REGISTRAR_INDEX`] judged it `Reasonable` or `KnownGood`.
+//!
+//! The trusted authority set must be the one that finalized the proven block. Proofs of blocks
+//! finalized after a GRANDPA authority set change fail with [`Error::UnknownAuthority`] or
+//! [`Error::InvalidSignature`] until the caller trusts the new set, as the crate does not follow
+//! set changes.
+//!
+//! The crate is `no_std` compatible, so the same checks can run inside a runtime.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use sp_consensus_grandpa::{AuthorityId, AuthorityList, AuthoritySignature, SetId};
+use sp_core_hashing::{blake2_128, blake2_256, twox_128, twox_64};
+use sp_runtime::{
+	traits::{Hash as HashT, Header as HeaderT},
+	RuntimeDebug,
+};
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	vec::Vec,
+};
+use sp_trie::{LayoutV1, StorageProof};
+
+#[cfg(test)]
+mod tests;
+
+/// Name of the PoE pallet in the node template runtime.
+pub const PALLET_NAME: &[u8] = b"PoeModule";
+
+/// Name of the PoE pallet's claim storage map.
+pub const STORAGE_NAME: &[u8] = b"Proofs";
+
+/// Name of the PoE pallet's storage map of revoked claims.
+pub const REVOCATIONS_STORAGE_NAME: &[u8] = b"Revocations";
+
+/// Name of the NFT pallet in the node template runtime.
+pub const NFTS_PALLET_NAME: &[u8] = b"Nfts";
+
+/// The NFT collection of claims, `PoeNftCollection` in the node template runtime.
+pub const CLAIM_COLLECTION: u32 = 0;
+
+/// Name of the identity pallet in the node template runtime.
+pub const IDENTITY_PALLET_NAME: &[u8] = b"Identity";
+
+/// The registrar whose judgements decide who may hold claims, `PoeRegistrar` in the node
+/// template runtime.
+pub const REGISTRAR_INDEX: u32 = 0;
+
+/// Everything needed to check a claim against a finalized block.
+///
+/// The fields match the JSON returned by `poe_getClaimProof`, with the hex strings decoded.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct ClaimProof<Header> {
+	/// Header of the block the claim was read at.
+	pub header: Header,
+	/// SCALE encoded GRANDPA finality proof for `header`.
+	pub finality_proof: Vec<u8>,
+	/// Trie nodes proving the claim's entries, its NFT and the identity of the NFT's holder under
+	/// the header's state root.
+	pub storage_proof: Vec<Vec<u8>>,
+}
+
+/// A GRANDPA authority set trusted by the verifier.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct AuthoritySet {
+	/// The authorities and their voting weights.
+	pub authorities: AuthorityList,
+	/// The id of the set, as signed over by its authorities.
+	pub set_id: SetId,
+}
+
+impl AuthoritySet {
+	fn weight_of(&self, id: &AuthorityId) -> Option<u64> {
+		self.authorities.iter().find(|(authority, _)| authority == id).map(|(_, weight)| *weight)
+	}
+
+	/// The weight of precommits needed to finalize a block, i.e. more than two thirds.
+	fn threshold(&self) -> u64 {
+		let total = self.authorities.iter().fold(0u64, |total, (_, w)| total.saturating_add(*w));
+		total - total.saturating_sub(1) / 3
+	}
+}
+
+/// A claim's entry in the `Proofs` map.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct ClaimRecord<AccountId, BlockNumber> {
+	/// The claim's owner.
+	pub owner: AccountId,
+	/// The block the claim was created in.
+	pub created_at: BlockNumber,
+}
+
+/// A claim's entry in the `Revocations` map.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct Revocation<AccountId, BlockNumber> {
+	/// The account that revoked the claim.
+	pub revoked_by: AccountId,
+	/// The block the claim was revoked in.
+	pub revoked_at: BlockNumber,
+}
+
+/// The state of a claim at the proven block.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ClaimStatus<AccountId, BlockNumber> {
+	/// The claim exists, with its owner resolved through its NFT.
+	Found(ClaimRecord<AccountId, BlockNumber>),
+	/// The claim was never created, or revoked longer than the runtime's `RevocationRetention`
+	/// blocks before.
+	NotFound,
+	/// The claim was revoked within the runtime's `RevocationRetention` blocks.
+	Revoked(Revocation<AccountId, BlockNumber>),
+}
+
+/// `pallet_identity::Judgement`, to decode the judgements of an identity.
+#[derive(Decode)]
+enum Judgement<Balance> {
+	Unknown,
+	FeePaid(#[allow(dead_code)] Balance),
+	Reasonable,
+	KnownGood,
+	OutOfDate,
+	LowQuality,
+	Erroneous,
+}
+
+/// `pallet_identity::RegistrarInfo`, to decode the registrars.
+#[derive(Decode)]
+struct RegistrarInfo<Balance, AccountId> {
+	_account: AccountId,
+	_fee: Balance,
+	_fields: u64,
+}
+
+/// A GRANDPA finality proof, encoded the same way as `sc_consensus_grandpa::FinalityProof`.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct FinalityProof<Header: HeaderT> {
+	/// The hash of the block the justification finalizes.
+	pub block: Header::Hash,
+	/// SCALE encoded [`GrandpaJustification`] of `block`.
+	pub justification: Vec<u8>,
+	/// Headers linking the proven block to `block`, oldest first.
+	pub unknown_headers: Vec<Header>,
+}
+
+/// A GRANDPA commit for a header.
+pub type Commit<Header> = finality_grandpa::Commit<
+	<Header as HeaderT>::Hash,
+	<Header as HeaderT>::Number,
+	AuthoritySignature,
+	AuthorityId,
+>;
+
+/// A GRANDPA justification, encoded the same way as `sc_consensus_grandpa::GrandpaJustification`.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct GrandpaJustification<Header: HeaderT> {
+	/// The round the commit was made in.
+	pub round: u64,
+	/// The commit finalizing the block.
+	pub commit: Commit<Header>,
+	/// Headers between the finalized block and the blocks precommits voted for.
+	pub votes_ancestries: Vec<Header>,
+}
+
+/// Why a claim proof was rejected.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Error {
+	/// The finality proof or the justification in it could not be decoded.
+	InvalidFinalityProof,
+	/// The unknown headers do not link the proven block to the justified one.
+	BrokenHeaderChain,
+	/// The justification commits to a different block than the finality proof.
+	JustificationTargetMismatch,
+	/// A precommit was signed by an authority outside the trusted set.
+	UnknownAuthority,
+	/// A precommit signature does not match its signer, round or set id.
+	InvalidSignature,
+	/// A precommit votes for a block that does not descend from the justified block.
+	InvalidAncestry,
+	/// The precommits carry less than two thirds of the authority set's weight.
+	NotEnoughVotes,
+	/// The storage proof does not match the header's state root.
+	InvalidStorageProof,
+	/// The claim's entries, its NFT or the identity of the NFT's holder could not be decoded.
+	InvalidRecord,
+}
+
+/// Verify `proof` for `claim` against the trusted `authority_set`, which must be the authority
+/// set that finalized the proven block.
+///
+/// Returns the state of the claim at the proven block. `Balance` is the balance type of the
+/// runtime, needed to decode identities.
+pub fn verify_claim<Header, AccountId, Balance>(
+	claim: &[u8],
+	proof: &ClaimProof<Header>,
+	authority_set: &AuthoritySet,
+) -> Result<ClaimStatus<AccountId, Header::Number>, Error>
+where
+	Header: HeaderT,
+	AccountId: Decode + Encode + PartialEq,
+	Balance: Decode,
+{
+	verify_finality(&proof.header, &proof.finality_proof, authority_set)?;
+	read_claim::<Header::Hashing, _, _, Balance>(
+		claim,
+		proof.header.state_root(),
+		&proof.storage_proof,
+	)
+}
+
+/// Check that `finality_proof` proves `header` final under `authority_set`.
+pub fn verify_finality<Header: HeaderT>(
+	header: &Header,
+	finality_proof: &[u8],
+	authority_set: &AuthoritySet,
+) -> Result<(), Error> {
+	let proof = FinalityProof::<Header>::decode(&mut &finality_proof[..])
+		.map_err(|_| Error::InvalidFinalityProof)?;
+
+	// 被证明的区块可能早于带 justification 的区块，需要沿着区块头连过去
+	let mut hash = header.hash();
+	let mut number = *header.number();
+	for next in &proof.unknown_headers {
+		if *next.parent_hash() != hash {
+			return Err(Error::BrokenHeaderChain)
+		}
+		hash = next.hash();
+		number = *next.number();
+	}
+	if hash != proof.block {
+		return Err(Error::BrokenHeaderChain)
+	}
+
+	let justification = GrandpaJustification::<Header>::decode(&mut &proof.justification[..])
+		.map_err(|_| Error::InvalidFinalityProof)?;
+	justification.verify(hash, number, authority_set)
+}
+
+impl<Header: HeaderT> GrandpaJustification<Header> {
+	/// Check that the justification finalizes block `number` with `hash` under `authority_set`.
+	pub fn verify(
+		&self,
+		hash: Header::Hash,
+		number: Header::Number,
+		authority_set: &AuthoritySet,
+	) -> Result<(), Error> {
+		if self.commit.target_hash != hash || self.commit.target_number != number {
+			return Err(Error::JustificationTargetMismatch)
+		}
+
+		let ancestry: BTreeMap<_, _> =
+			self.votes_ancestries.iter().map(|header| (header.hash(), header)).collect();
+		let mut signers = BTreeSet::new();
+		let mut weight = 0u64;
+
+		for signed in &self.commit.precommits {
+			let authority_weight =
+				authority_set.weight_of(&signed.id).ok_or(Error::UnknownAuthority)?;
+			let message = finality_grandpa::Message::Precommit(signed.precommit.clone());
+			if !sp_consensus_grandpa::check_message_signature(
+				&message,
+				&signed.id,
+				&signed.signature,
+				self.round,
+				authority_set.set_id,
+			) {
+				return Err(Error::InvalidSignature)
+			}
+			if !descends_from::<Header>(&ancestry, signed.precommit.target_hash, hash) {
+				return Err(Error::InvalidAncestry)
+			}
+			// 同一验证人的多个投票只计一次权重
+			if signers.insert(signed.id.clone()) {
+				weight = weight.saturating_add(authority_weight);
+			}
+		}
+
+		if weight == 0 || weight < authority_set.threshold() {
+			return Err(Error::NotEnoughVotes)
+		}
+		Ok(())
+	}
+}
+
+fn descends_from<Header: HeaderT>(
+	ancestry: &BTreeMap<Header::Hash, &Header>,
+	mut hash: Header::Hash,
+	base: Header::Hash,
+) -> bool {
+	while hash != base {
+		match ancestry.get(&hash) {
+			Some(header) => hash = *header.parent_hash(),
+			None => return false,
+		}
+	}
+	true
+}
+
+/// Read the state of `claim` from `storage_proof` under `state_root`, resolving its owner the
+/// way `pallet_poe` does: the holder of the claim's NFT owns it rather than the `Proofs` owner,
+/// which lags behind while the NFT is transferred on its own, unless registrar
+/// [`REGISTRAR_INDEX`] did not judge the holder `Reasonable` or `KnownGood`.
+pub fn read_claim<Hashing, AccountId, BlockNumber, Balance>(
+	claim: &[u8],
+	state_root: &Hashing::Output,
+	storage_proof: &[Vec<u8>],
+) -> Result<ClaimStatus<AccountId, BlockNumber>, Error>
+where
+	Hashing: HashT,
+	AccountId: Decode + Encode + PartialEq,
+	BlockNumber: Decode,
+	Balance: Decode,
+{
+	let db = StorageProof::new(storage_proof.iter().cloned()).into_memory_db::<Hashing>();
+	let read = |key: &[u8]| {
+		sp_trie::read_trie_value::<LayoutV1<Hashing>, _>(&db, state_root, key, None, None)
+			.map_err(|_| Error::InvalidStorageProof)
+	};
+
+	let mut record = match read(&claim_key(claim))? {
+		Some(value) => ClaimRecord::<AccountId, BlockNumber>::decode(&mut &value[..])
+			.map_err(|_| Error::InvalidRecord)?,
+		None =>
+			return match read(&revocation_key(claim))? {
+				Some(value) => Revocation::decode(&mut &value[..])
+					.map(ClaimStatus::Revoked)
+					.map_err(|_| Error::InvalidRecord),
+				None => Ok(ClaimStatus::NotFound),
+			},
+	};
+
+	// pallet_nfts::ItemDetails 的第一个字段是所有者
+	let holder = read(&nft_key(claim))?
+		.map(|value| AccountId::decode(&mut &value[..]).map_err(|_| Error::InvalidRecord))
+		.transpose()?;
+	if let Some(holder) = holder {
+		if holder != record.owner && is_verified::<_, Balance>(&holder, read)? {
+			record.owner = holder;
+		}
+	}
+	Ok(ClaimStatus::Found(record))
+}
+
+/// Whether registrar [`REGISTRAR_INDEX`] exists and judged `who` `Reasonable` or `KnownGood`,
+/// reading storage through `read`.
+fn is_verified<AccountId, Balance>(
+	who: &AccountId,
+	read: impl Fn(&[u8]) -> Result<Option<Vec<u8>>, Error>,
+) -> Result<bool, Error>
+where
+	AccountId: Decode + Encode,
+	Balance: Decode,
+{
+	let registrars = match read(&registrars_key())? {
+		Some(value) => Vec::<Option<RegistrarInfo<Balance, AccountId>>>::decode(&mut &value[..])
+			.map_err(|_| Error::InvalidRecord)?,
+		None => return Ok(false),
+	};
+	if !matches!(registrars.get(REGISTRAR_INDEX as usize), Some(Some(_))) {
+		return Ok(false)
+	}
+
+	// pallet_identity::Registration 的第一个字段是评判
+	let judgements = match read(&identity_key(who))? {
+		Some(value) => Vec::<(u32, Judgement<Balance>)>::decode(&mut &value[..])
+			.map_err(|_| Error::InvalidRecord)?,
+		None => return Ok(false),
+	};
+	Ok(judgements.iter().any(|(index, judgement)| {
+		*index == REGISTRAR_INDEX &&
+			matches!(judgement, Judgement::Reasonable | Judgement::KnownGood)
+	}))
+}
+
+/// The storage key of `claim` in the PoE pallet's `Proofs` map.
+pub fn claim_key(claim: &[u8]) -> Vec<u8> {
+	map_key(PALLET_NAME, STORAGE_NAME, &blake2_128_concat(&claim.encode()))
+}
+
+/// The storage key of `claim` in the PoE pallet's `Revocations` map.
+pub fn revocation_key(claim: &[u8]) -> Vec<u8> {
+	map_key(PALLET_NAME, REVOCATIONS_STORAGE_NAME, &blake2_128_concat(&claim.encode()))
+}
+
+/// The storage key of the NFT of `claim` in the NFT pallet's `Item` map. The item id is the
+/// blake2-256 hash of the claim.
+pub fn nft_key(claim: &[u8]) -> Vec<u8> {
+	let collection = blake2_128_concat(&CLAIM_COLLECTION.encode());
+	let item = blake2_128_concat(&blake2_256(claim));
+	map_key(NFTS_PALLET_NAME, b"Item", &[collection, item].concat())
+}
+
+/// The storage key of the identity pallet's `Registrars`.
+pub fn registrars_key() -> Vec<u8> {
+	map_key(IDENTITY_PALLET_NAME, b"Registrars", &[])
+}
+
+/// The storage key of the identity of `who` in the identity pallet's `IdentityOf` map.
+pub fn identity_key<AccountId: Encode>(who: &AccountId) -> Vec<u8> {
+	let who = who.encode();
+	map_key(IDENTITY_PALLET_NAME, b"IdentityOf", &[&twox_64(&who)[..], &who[..]].concat())
+}
+
+fn map_key(pallet: &[u8], storage: &[u8], hashed_key: &[u8]) -> Vec<u8> {
+	[&twox_128(pallet)[..], &twox_128(storage)[..], hashed_key].concat()
+}
+
+fn blake2_128_concat(data: &[u8]) -> Vec<u8> {
+	[&blake2_128(data)[..], data].concat()
+}
@@ -0,0 +1,251 @@
+use crate::*;
+use sp_keyring::Ed25519Keyring;
+use sp_runtime::{generic, traits::BlakeTwo256};
+use sp_trie::{MemoryDB, TrieDBMutBuilderV1, TrieMut};
+
+type Header = generic::Header<u32, BlakeTwo256>;
+type Hash = <Header as HeaderT>::Hash;
+
+const SET_ID: SetId = 1;
+const ROUND: u64 = 7;
+
+/// Build a state containing `claims` and return its root and the nodes proving it.
+fn state(claims: &[(&[u8], ClaimRecord<u64, u32>)]) -> (Hash, Vec<Vec<u8>>) {
+	state_with(claims, &[])
+}
+
+/// Build a state containing `claims` and the raw `entries`, and return its root and the nodes
+/// proving it.
+fn state_with(
+	claims: &[(&[u8], ClaimRecord<u64, u32>)],
+	entries: &[(Vec<u8>, Vec<u8>)],
+) -> (Hash, Vec<Vec<u8>>) {
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let mut root = Hash::default();
+	{
+		let mut trie = TrieDBMutBuilderV1::<BlakeTwo256>::new(&mut db, &mut root).build();
+		trie.insert(b":code", b"runtime").unwrap();
+		for (claim, record) in claims {
+			trie.insert(&claim_key(claim), &record.encode()).unwrap();
+		}
+		for (key, value) in entries {
+			trie.insert(key, value).unwrap();
+		}
+	}
+	let nodes = db.drain().into_values().map(|(node, _)| node).collect();
+	(root, nodes)
+}
+
+fn header(number: u32, parent_hash: Hash, state_root: Hash) -> Header {
+	Header::new(number, Default::default(), state_root, parent_hash, Default::default())
+}
+
+fn authority_set(keys: &[Ed25519Keyring]) -> AuthoritySet {
+	AuthoritySet {
+		authorities: keys.iter().map(|key| (key.public().into(), 1)).collect(),
+		set_id: SET_ID,
+	}
+}
+
+/// A finality proof for `header` carrying a justification of its last descendant in
+/// `unknown_headers`, signed by `signers`.
+fn finality_proof(
+	header: &Header,
+	unknown_headers: Vec<Header>,
+	signers: &[Ed25519Keyring],
+) -> Vec<u8> {
+	let target = unknown_headers.last().unwrap_or(header);
+	let precommit =
+		finality_grandpa::Precommit { target_hash: target.hash(), target_number: target.number };
+	let message = finality_grandpa::Message::Precommit(precommit.clone());
+	let payload = sp_consensus_grandpa::localized_payload(ROUND, SET_ID, &message);
+
+	let precommits = signers
+		.iter()
+		.map(|key| finality_grandpa::SignedPrecommit {
+			precommit: precommit.clone(),
+			signature: key.sign(&payload).into(),
+			id: key.public().into(),
+		})
+		.collect();
+	let justification = GrandpaJustification::<Header> {
+		round: ROUND,
+		commit: finality_grandpa::Commit {
+			target_hash: target.hash(),
+			target_number: target.number,
+			precommits,
+		},
+		votes_ancestries: vec![],
+	};
+
+	FinalityProof::<Header> {
+		block: target.hash(),
+		justification: justification.encode(),
+		unknown_headers,
+	}
+	.encode()
+}
+
+fn record() -> ClaimRecord<u64, u32> {
+	ClaimRecord { owner: 1, created_at: 3 }
+}
+
+#[test]
+fn verifies_existing_claim() {
+	let (root, storage_proof) = state(&[(&b"claim"[..], record())]);
+	let header = header(5, Default::default(), root);
+	let finality_proof = finality_proof(&header, vec![], &[Ed25519Keyring::Alice]);
+	let proof = ClaimProof { header, finality_proof, storage_proof };
+
+	assert_eq!(
+		verify_claim::<_, u64, u64>(b"claim", &proof, &authority_set(&[Ed25519Keyring::Alice])),
+		Ok(ClaimStatus::Found(record()))
+	);
+}
+
+#[test]
+fn verifies_missing_claim() {
+	let (root, storage_proof) = state(&[(&b"claim"[..], record())]);
+	let header = header(5, Default::default(), root);
+	let finality_proof = finality_proof(&header, vec![], &[Ed25519Keyring::Alice]);
+	let proof = ClaimProof { header, finality_proof, storage_proof };
+
+	assert_eq!(
+		verify_claim::<_, u64, u64>(b"other", &proof, &authority_set(&[Ed25519Keyring::Alice])),
+		Ok(ClaimStatus::NotFound)
+	);
+}
+
+#[test]
+fn follows_unknown_headers_to_the_justified_block() {
+	let (root, storage_proof) = state(&[(&b"claim"[..], record())]);
+	let header = header(5, Default::default(), root);
+	let child = self::header(6, header.hash(), root);
+	let grandchild = self::header(7, child.hash(), root);
+	let set = authority_set(&[Ed25519Keyring::Alice]);
+
+	let finality_proof =
+		finality_proof(&header, vec![child.clone(), grandchild], &[Ed25519Keyring::Alice]);
+	let proof = ClaimProof { header: header.clone(), finality_proof, storage_proof };
+	assert_eq!(
+		verify_claim::<_, u64, u64>(b"claim", &proof, &set),
+		Ok(ClaimStatus::Found(record()))
+	);
+
+	// 区块头链断开
+	let stranger = self::header(7, Hash::repeat_byte(1), root);
+	let finality_proof =
+		self::finality_proof(&header, vec![child, stranger], &[Ed25519Keyring::Alice]);
+	assert_eq!(verify_finality(&header, &finality_proof, &set), Err(Error::BrokenHeaderChain));
+}
+
+#[test]
+fn rejects_untrusted_or_insufficient_votes() {
+	let (root, _) = state(&[]);
+	let header = header(5, Default::default(), root);
+
+	let finality_proof = finality_proof(&header, vec![], &[Ed25519Keyring::Alice]);
+	assert_eq!(
+		verify_finality(&header, &finality_proof, &authority_set(&[Ed25519Keyring::Bob])),
+		Err(Error::UnknownAuthority)
+	);
+
+	let set =
+		authority_set(&[Ed25519Keyring::Alice, Ed25519Keyring::Bob, Ed25519Keyring::Charlie]);
+	let finality_proof =
+		self::finality_proof(&header, vec![], &[Ed25519Keyring::Alice, Ed25519Keyring::Bob]);
+	assert_eq!(verify_finality(&header, &finality_proof, &set), Err(Error::NotEnoughVotes));
+
+	// 重复的投票不能凑够权重
+	let finality_proof =
+		self::finality_proof(&header, vec![], &[Ed25519Keyring::Alice, Ed25519Keyring::Alice]);
+	assert_eq!(verify_finality(&header, &finality_proof, &set), Err(Error::NotEnoughVotes));
+
+	let finality_proof = self::finality_proof(
+		&header,
+		vec![],
+		&[Ed25519Keyring::Alice, Ed25519Keyring::Bob, Ed25519Keyring::Charlie],
+	);
+	assert_eq!(verify_finality(&header, &finality_proof, &set), Ok(()));
+
+	// 空的验证人集合不能确认任何区块
+	let finality_proof = self::finality_proof(&header, vec![], &[]);
+	assert_eq!(
+		verify_finality(&header, &finality_proof, &authority_set(&[])),
+		Err(Error::NotEnoughVotes)
+	);
+}
+
+#[test]
+fn rejects_signatures_for_another_set() {
+	let (root, _) = state(&[]);
+	let header = header(5, Default::default(), root);
+	let finality_proof = finality_proof(&header, vec![], &[Ed25519Keyring::Alice]);
+	let mut set = authority_set(&[Ed25519Keyring::Alice]);
+	set.set_id += 1;
+
+	assert_eq!(verify_finality(&header, &finality_proof, &set), Err(Error::InvalidSignature));
+}
+
+#[test]
+fn rejects_storage_proof_for_another_state() {
+	let (_, storage_proof) = state(&[(&b"claim"[..], record())]);
+	let (other_root, _) = state(&[(&b"claim"[..], ClaimRecord { owner: 2, created_at: 3 })]);
+	let header = header(5, Default::default(), other_root);
+	let finality_proof = finality_proof(&header, vec![], &[Ed25519Keyring::Alice]);
+	let proof = ClaimProof { header, finality_proof, storage_proof };
+
+	assert_eq!(
+		verify_claim::<_, u64, u64>(b"claim", &proof, &authority_set(&[Ed25519Keyring::Alice])),
+		Err(Error::InvalidStorageProof)
+	);
+}
+
+/// Verify `claim` with a proof of a finalized block whose state holds `claims` and `entries`.
+fn verify(
+	claim: &[u8],
+	claims: &[(&[u8], ClaimRecord<u64, u32>)],
+	entries: &[(Vec<u8>, Vec<u8>)],
+) -> Result<ClaimStatus<u64, u32>, Error> {
+	let (root, storage_proof) = state_with(claims, entries);
+	let header = header(5, Default::default(), root);
+	let finality_proof = finality_proof(&header, vec![], &[Ed25519Keyring::Alice]);
+	let proof = ClaimProof { header, finality_proof, storage_proof };
+
+	verify_claim::<_, u64, u64>(claim, &proof, &authority_set(&[Ed25519Keyring::Alice]))
+}
+
+#[test]
+fn verifies_revoked_claim() {
+	let revocation = Revocation { revoked_by: 2u64, revoked_at: 4u32 };
+	let entries = [(revocation_key(b"claim"), revocation.encode())];
+
+	assert_eq!(verify(b"claim", &[], &entries), Ok(ClaimStatus::Revoked(revocation)));
+	assert_eq!(verify(b"other", &[], &entries), Ok(ClaimStatus::NotFound));
+}
+
+#[test]
+fn resolves_owner_through_verified_nft_holder() {
+	let claims = [(&b"claim"[..], record())];
+	let holder = 2u64;
+	let nft = (nft_key(b"claim"), holder.encode());
+	// 登记员的账户、费用和字段，以及 `KnownGood` 评判
+	let registrars = (registrars_key(), vec![Some((9u64, 0u64, 0u64))].encode());
+	let identity = (identity_key(&holder), vec![(REGISTRAR_INDEX, 3u8)].encode());
+
+	// 持有者没有通过身份要求时，所有者仍是 `Proofs` 中的账户
+	assert_eq!(verify(b"claim", &claims, &[nft.clone()]), Ok(ClaimStatus::Found(record())));
+	assert_eq!(
+		verify(b"claim", &claims, &[nft.clone(), identity.clone()]),
+		Ok(ClaimStatus::Found(record()))
+	);
+	assert_eq!(
+		verify(b"claim", &claims, &[nft.clone(), registrars.clone()]),
+		Ok(ClaimStatus::Found(record()))
+	);
+
+	assert_eq!(
+		verify(b"claim", &claims, &[nft, registrars, identity]),
+		Ok(ClaimStatus::Found(ClaimRecord { owner: holder, created_at: 3 }))
+	);
+}
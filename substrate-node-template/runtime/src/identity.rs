@@ -0,0 +1,59 @@
+//! Identity requirements of the runtime for claimants of `PoeModule`.
+
+use crate::{AccountId, Balance, Runtime};
+use frame_support::{parameter_types, traits::Contains, BoundedVec};
+use pallet_identity::{Judgement, RegistrarIndex, RegistrarInfo};
+
+parameter_types! {
+	/// The registrar whose judgements decide who may hold claims.
+	pub const PoeRegistrar: RegistrarIndex = 0;
+}
+
+/// An identity as stored in `pallet_identity::IdentityOf`.
+pub type IdentityRegistration = pallet_identity::Registration<
+	Balance,
+	<Runtime as pallet_identity::Config>::MaxRegistrars,
+	<Runtime as pallet_identity::Config>::MaxAdditionalFields,
+>;
+
+/// The registrars as stored in `pallet_identity::Registrars`.
+pub type IdentityRegistrars = BoundedVec<
+	Option<RegistrarInfo<Balance, AccountId>>,
+	<Runtime as pallet_identity::Config>::MaxRegistrars,
+>;
+
+/// Accounts judged `Reasonable` or `KnownGood` by the [`PoeRegistrar`].
+///
+/// No account passes until the registrar is added with `Identity::add_registrar`. Development
+/// chains start with the registrar and verified identities for their endowed accounts.
+pub struct VerifiedIdentity;
+
+impl VerifiedIdentity {
+	/// Whether an account with `registration` passes, given the `registrars` of
+	/// `pallet_identity`. Lets the node check accounts against storage it read itself.
+	pub fn is_verified(
+		registrars: &[Option<RegistrarInfo<Balance, AccountId>>],
+		registration: Option<&IdentityRegistration>,
+	) -> bool {
+		let registrar = PoeRegistrar::get();
+		if !matches!(registrars.get(registrar as usize), Some(Some(_))) {
+			return false
+		}
+
+		registration.map_or(false, |registration| {
+			registration.judgements.iter().any(|(index, judgement)| {
+				*index == registrar &&
+					matches!(judgement, Judgement::Reasonable | Judgement::KnownGood)
+			})
+		})
+	}
+}
+
+impl Contains<AccountId> for VerifiedIdentity {
+	fn contains(who: &AccountId) -> bool {
+		Self::is_verified(
+			&pallet_identity::Pallet::<Runtime>::registrars(),
+			pallet_identity::Pallet::<Runtime>::identity(who).as_ref(),
+		)
+	}
+}
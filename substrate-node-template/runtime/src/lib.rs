@@ -6,6 +6,7 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::Encode;
 use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -13,10 +14,11 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Extrinsic as ExtrinsicT,
+		IdentifyAccount, NumberFor, One, Verify,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, MultiSignature, SaturatedConversion,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -41,6 +43,8 @@ pub use frame_support::{
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
+use frame_support::{traits::AsEnsureOriginWithArg, PalletId};
+use pallet_nfts::{CollectionConfig, CollectionSettings, PalletFeatures};
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -49,6 +53,18 @@ pub use sp_runtime::{Perbill, Permill};
 /// Import the template pallet.
 pub use pallet_template;
 
+/// Import the proof of existence pallet.
+pub use pallet_poe;
+
+mod identity;
+pub use identity::{IdentityRegistrars, IdentityRegistration, PoeRegistrar, VerifiedIdentity};
+
+mod proxy;
+pub use proxy::ProxyType;
+
+#[cfg(test)]
+mod tests;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -231,6 +247,11 @@ impl pallet_timestamp::Config for Runtime {
 /// Existential deposit.
 pub const EXISTENTIAL_DEPOSIT: u128 = 500;
 
+/// Deposit reserved for `items` storage items taking `bytes` bytes in total.
+pub const fn deposit(items: u32, bytes: u32) -> Balance {
+	items as Balance * 100 * EXISTENTIAL_DEPOSIT + bytes as Balance * EXISTENTIAL_DEPOSIT
+}
+
 impl pallet_balances::Config for Runtime {
 	type MaxLocks = ConstU32<50>;
 	type MaxReserves = ();
@@ -268,15 +289,249 @@ impl pallet_sudo::Config for Runtime {
 	type WeightInfo = pallet_sudo::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = deposit(1, 40);
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = deposit(0, 33);
+	// One storage item; key size 32, value size 16.
+	pub const AnnouncementDepositBase: Balance = deposit(1, 48);
+	// Additional storage item size of 68 bytes.
+	pub const AnnouncementDepositFactor: Balance = deposit(0, 68);
+}
+
+impl pallet_proxy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = ConstU32<32>;
+	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+	type MaxPending = ConstU32<32>;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = frame_support::traits::EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+	pub const PreimageByteDeposit: Balance = deposit(0, 1);
+}
+
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	// One storage item; 258 bytes on-chain.
+	pub const IdentityBasicDeposit: Balance = deposit(1, 258);
+	// Additional 66 bytes per field.
+	pub const IdentityFieldDeposit: Balance = deposit(0, 66);
+	// One storage item; 53 bytes on-chain.
+	pub const IdentitySubAccountDeposit: Balance = deposit(1, 53);
+}
+
+impl pallet_identity::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BasicDeposit = IdentityBasicDeposit;
+	type FieldDeposit = IdentityFieldDeposit;
+	type SubAccountDeposit = IdentitySubAccountDeposit;
+	type MaxSubAccounts = ConstU32<100>;
+	type MaxAdditionalFields = ConstU32<100>;
+	type MaxRegistrars = ConstU32<20>;
+	type Slashed = ();
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type RegistrarOrigin = frame_system::EnsureRoot<AccountId>;
+	type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const NftsCollectionDeposit: Balance = deposit(1, 130);
+	pub const NftsItemDeposit: Balance = deposit(1, 164);
+	pub const NftsMetadataDepositBase: Balance = deposit(1, 129);
+	pub const NftsAttributeDepositBase: Balance = deposit(1, 0);
+	pub const NftsDepositPerByte: Balance = deposit(0, 1);
+	pub const NftsMaxDeadlineDuration: BlockNumber = 12 * 30 * DAYS;
+	pub NftsFeatures: PalletFeatures = PalletFeatures::all_enabled();
+}
+
+/// NFT items are identified by a hash, so claims can be mapped to items without an index.
+impl pallet_nfts::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type CollectionId = u32;
+	type ItemId = Hash;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type Locker = ();
+	type CollectionDeposit = NftsCollectionDeposit;
+	type ItemDeposit = NftsItemDeposit;
+	type MetadataDepositBase = NftsMetadataDepositBase;
+	type AttributeDepositBase = NftsAttributeDepositBase;
+	type DepositPerByte = NftsDepositPerByte;
+	type StringLimit = ConstU32<256>;
+	type KeyLimit = ConstU32<64>;
+	type ValueLimit = ConstU32<256>;
+	type ApprovalsLimit = ConstU32<20>;
+	type ItemAttributesApprovalsLimit = ConstU32<20>;
+	type MaxTips = ConstU32<10>;
+	type MaxDeadlineDuration = NftsMaxDeadlineDuration;
+	type MaxAttributesPerCall = ConstU32<10>;
+	type Features = NftsFeatures;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type WeightInfo = pallet_nfts::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type Helper = NftsBenchmarkHelper;
+}
+
+/// Collection and item ids for the `pallet_nfts` benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct NftsBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_nfts::BenchmarkHelper<u32, Hash> for NftsBenchmarkHelper {
+	fn collection(i: u16) -> u32 {
+		i.into()
+	}
+
+	fn item(i: u16) -> Hash {
+		Hash::from_low_u64_be(i.into())
+	}
+}
+
+parameter_types! {
+	/// A commitment is a 32 byte hash mapping to its committer, block and deposit.
+	pub const PoeCommitDeposit: Balance = deposit(1, 84);
+	pub const PoeCreatorRoyalty: Perbill = Perbill::from_percent(5);
+	/// Claims are minted into this collection, created at genesis as the first one.
+	pub const PoeNftCollection: u32 = 0;
+	/// Keyless owner and admin of the collection of claims, so that nobody can change the
+	/// collection or mint into it but `pallet_poe`.
+	pub PoeNftOwner: AccountId = PalletId(*b"poe/nfts").into_account_truncating();
+	pub PoeNftCollectionConfig: CollectionConfig<Balance, BlockNumber, u32> = CollectionConfig {
+		settings: CollectionSettings::all_enabled(),
+		max_supply: None,
+		mint_settings: Default::default(),
+	};
+}
+
+/// The limits below bound the parameters governance can set with `PoeModule::set_parameters`.
 impl pallet_poe::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type MaxClaimLength = ConstU32<512>;
+	type RevealWindow = ConstU32<{ 10 * MINUTES }>;
+	type MaxCommitmentsPerBlock = ConstU32<256>;
+	type CommitDeposit = PoeCommitDeposit;
+	type MaxClaimsPerBlock = ConstU32<256>;
+	type MaxClaimsPerAccount = ConstU32<32>;
+	type RateLimitWindow = ConstU32<{ 10 * MINUTES }>;
+	type AuthorityId = pallet_poe::crypto::AuthorityId;
+	type AvailabilityOrigin = pallet_poe::EnsureAvailabilitySigner<Runtime>;
+	type AvailabilitySignerOrigin = frame_system::EnsureRoot<AccountId>;
+	type VerificationInterval = ConstU32<HOURS>;
+	type MaxVerificationsPerRun = ConstU32<16>;
+	type MaxContentLength = ConstU32<{ 64 * 1024 }>;
+	type AttesterOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxAttestationsPerClaim = ConstU32<16>;
+	type MaxAttestationTypeLength = ConstU32<32>;
+	type ParametersOrigin = frame_system::EnsureRoot<AccountId>;
+	type RuntimeCall = RuntimeCall;
+	type Scheduler = Scheduler;
+	type Preimages = Preimage;
+	type Currency = Balances;
+	type CreatorRoyalty = PoeCreatorRoyalty;
+	type MaxLicensesPerClaim = ConstU32<64>;
+	type MaxLicenseExpiriesPerBlock = ConstU32<256>;
+	type RevocationRetention = ConstU32<{ 30 * DAYS }>;
+	type MaxRevocationsPerBlock = ConstU32<256>;
+	type Nfts = pallet_poe::NftsAdapter<
+		Nfts,
+		PoeNftCollection,
+		PoeNftOwner,
+		PoeNftCollectionConfig,
+		pallet_nfts::ItemConfig,
+	>;
+	type ClaimantFilter = VerifiedIdentity;
+	type ClaimValidator = pallet_poe::MinLength<ConstU32<1>>;
+	type OnClaimCreated = ();
+	type OnClaimTransferred = ();
+	type OnClaimRevoked = ();
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Nonce,
+	) -> Option<(RuntimeCall, <UncheckedExtrinsic as ExtrinsicT>::SignaturePayload)> {
+		let period =
+			BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+			pallet_poe::ValidateClaim::<Runtime>::new(),
+		);
+		let raw_payload = SignedPayload::new(call, extra).ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (Address::Id(account), signature, extra)))
+	}
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = UncheckedExtrinsic;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -289,6 +544,11 @@ construct_runtime!(
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
 		Sudo: pallet_sudo,
+		Proxy: pallet_proxy,
+		Scheduler: pallet_scheduler,
+		Preimage: pallet_preimage,
+		Nfts: pallet_nfts,
+		Identity: pallet_identity,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
 		PoeModule: pallet_poe,
@@ -311,6 +571,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_poe::ValidateClaim<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -339,6 +600,11 @@ mod benches {
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[pallet_sudo, Sudo]
+		[pallet_proxy, Proxy]
+		[pallet_scheduler, Scheduler]
+		[pallet_preimage, Preimage]
+		[pallet_nfts, Nfts]
+		[pallet_identity, Identity]
 		[pallet_template, TemplateModule]
 	);
 }
@@ -511,6 +777,25 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_poe_runtime_api::PoeApi<
+		Block,
+		AccountId,
+		pallet_poe::AttestationOf<Runtime>,
+		pallet_poe::LicenseOf<Runtime>,
+	> for Runtime {
+		fn attestations(claim: Vec<u8>) -> Vec<pallet_poe::AttestationOf<Runtime>> {
+			PoeModule::active_attestations(&claim)
+		}
+
+		fn licenses(claim: Vec<u8>) -> Vec<pallet_poe::LicenseOf<Runtime>> {
+			PoeModule::active_licenses(&claim)
+		}
+
+		fn licenses_of(licensee: AccountId) -> Vec<(Vec<u8>, pallet_poe::LicenseOf<Runtime>)> {
+			PoeModule::licenses_of(&licensee)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -0,0 +1,157 @@
+//! Proxy types of the runtime, restricting what a proxy account can do on behalf of another.
+
+use crate::RuntimeCall;
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::traits::InstanceFilter;
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// The calls a proxy is allowed to make.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Encode,
+	Decode,
+	RuntimeDebug,
+	MaxEncodedLen,
+	TypeInfo,
+	Default,
+)]
+pub enum ProxyType {
+	/// Any call.
+	#[default]
+	Any,
+	/// Only `PoeModule` calls, e.g. for a service managing the claims of an account without
+	/// access to its funds. Selling and buying claims and committing to claims, whose deposit is
+	/// forfeited if the commitment expires, are left out.
+	PoeOnly,
+	/// Only calls creating claims, e.g. for a notarization service. Committing to claims is left
+	/// out, for the same reason as for `PoeOnly`.
+	PoeCreateOnly,
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+	fn filter(&self, call: &RuntimeCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::PoeOnly => matches!(
+				call,
+				RuntimeCall::PoeModule(
+					pallet_poe::Call::create_claim { .. } |
+						pallet_poe::Call::create_claim_with_content { .. } |
+						pallet_poe::Call::reveal_claim { .. } |
+						pallet_poe::Call::revoke_claim { .. } |
+						pallet_poe::Call::transfer_claim { .. } |
+						pallet_poe::Call::sync_claim_owner { .. } |
+						pallet_poe::Call::schedule_transfer { .. } |
+						pallet_poe::Call::schedule_revoke { .. } |
+						pallet_poe::Call::cancel_scheduled { .. } |
+						pallet_poe::Call::unlist_claim { .. } |
+						pallet_poe::Call::attest_claim { .. } |
+						pallet_poe::Call::revoke_attestation { .. } |
+						pallet_poe::Call::grant_license { .. } |
+						pallet_poe::Call::revoke_license { .. } |
+						pallet_poe::Call::approve { .. } |
+						pallet_poe::Call::cancel_approval { .. } |
+						pallet_poe::Call::set_approval_for_all { .. }
+				)
+			),
+			ProxyType::PoeCreateOnly => matches!(
+				call,
+				RuntimeCall::PoeModule(
+					pallet_poe::Call::create_claim { .. } |
+						pallet_poe::Call::create_claim_with_content { .. } |
+						pallet_poe::Call::reveal_claim { .. }
+				)
+			),
+		}
+	}
+
+	fn is_superset(&self, other: &Self) -> bool {
+		match (self, other) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::PoeOnly, ProxyType::PoeCreateOnly) => true,
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{AccountId, BalancesCall};
+
+	fn create_claim() -> RuntimeCall {
+		pallet_poe::Call::create_claim { claim: vec![0, 1] }.into()
+	}
+
+	fn transfer_claim() -> RuntimeCall {
+		pallet_poe::Call::transfer_claim { claim: vec![0, 1], dest: AccountId::new([1; 32]) }
+			.into()
+	}
+
+	fn buy_claim() -> RuntimeCall {
+		pallet_poe::Call::buy_claim { claim: vec![0, 1], max_price: 1 }.into()
+	}
+
+	fn list_claim() -> RuntimeCall {
+		pallet_poe::Call::list_claim { claim: vec![0, 1], price: 1 }.into()
+	}
+
+	fn commit_claim() -> RuntimeCall {
+		pallet_poe::Call::commit_claim { commitment: Default::default() }.into()
+	}
+
+	fn transfer_funds() -> RuntimeCall {
+		BalancesCall::transfer_keep_alive { dest: AccountId::new([1; 32]).into(), value: 1 }.into()
+	}
+
+	#[test]
+	fn poe_only_allows_poe_calls() {
+		assert!(ProxyType::PoeOnly.filter(&create_claim()));
+		assert!(ProxyType::PoeOnly.filter(&transfer_claim()));
+		assert!(!ProxyType::PoeOnly.filter(&transfer_funds()));
+	}
+
+	#[test]
+	fn poe_proxies_cannot_spend_funds_through_poe_calls() {
+		for proxy_type in [ProxyType::PoeOnly, ProxyType::PoeCreateOnly] {
+			assert!(!proxy_type.filter(&buy_claim()));
+			assert!(!proxy_type.filter(&list_claim()));
+			assert!(!proxy_type.filter(&commit_claim()));
+		}
+		assert!(ProxyType::Any.filter(&buy_claim()));
+	}
+
+	#[test]
+	fn poe_create_only_allows_claim_creation() {
+		assert!(ProxyType::PoeCreateOnly.filter(&create_claim()));
+		assert!(ProxyType::PoeCreateOnly
+			.filter(&pallet_poe::Call::create_claim_with_content { content: vec![0] }.into()));
+		assert!(!ProxyType::PoeCreateOnly.filter(&transfer_claim()));
+		assert!(!ProxyType::PoeCreateOnly
+			.filter(&pallet_poe::Call::revoke_claim { claim: vec![0, 1] }.into()));
+		assert!(!ProxyType::PoeCreateOnly.filter(&transfer_funds()));
+	}
+
+	#[test]
+	fn any_allows_everything() {
+		assert!(ProxyType::Any.filter(&transfer_funds()));
+		assert!(ProxyType::Any.filter(&transfer_claim()));
+	}
+
+	#[test]
+	fn superset_follows_allowed_calls() {
+		assert!(ProxyType::Any.is_superset(&ProxyType::PoeOnly));
+		assert!(ProxyType::PoeOnly.is_superset(&ProxyType::PoeCreateOnly));
+		assert!(ProxyType::PoeCreateOnly.is_superset(&ProxyType::PoeCreateOnly));
+		assert!(!ProxyType::PoeCreateOnly.is_superset(&ProxyType::PoeOnly));
+		assert!(!ProxyType::PoeOnly.is_superset(&ProxyType::Any));
+	}
+}
@@ -0,0 +1,147 @@
+//! Tests of the pallets working together in the runtime.
+
+use crate::{
+	AccountId, BalancesConfig, Hash, Identity, Nfts, NftsCollectionDeposit, PoeModule,
+	PoeNftCollection, PoeNftOwner, PoeRegistrar, Runtime, RuntimeCall, RuntimeGenesisConfig,
+	RuntimeOrigin, System, EXISTENTIAL_DEPOSIT,
+};
+use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo, BoundedVec};
+use pallet_identity::{Data, IdentityInfo, Judgement};
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash as HashT},
+	BuildStorage,
+};
+
+fn alice() -> AccountId {
+	AccountId::new([1; 32])
+}
+
+fn bob() -> AccountId {
+	AccountId::new([2; 32])
+}
+
+fn registrar() -> AccountId {
+	AccountId::new([3; 32])
+}
+
+fn bounded(claim: &[u8]) -> BoundedVec<u8, <Runtime as pallet_poe::Config>::MaxClaimLength> {
+	BoundedVec::try_from(claim.to_vec()).unwrap()
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let balances = vec![
+		(alice(), 1 << 60),
+		(bob(), 1 << 60),
+		(PoeNftOwner::get(), EXISTENTIAL_DEPOSIT + NftsCollectionDeposit::get()),
+	];
+	let storage =
+		RuntimeGenesisConfig { balances: BalancesConfig { balances }, ..Default::default() }
+			.build_storage()
+			.unwrap();
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Add [`registrar`] as the `PoeRegistrar` unless it already is, and have it judge `who`
+/// `KnownGood`.
+fn verify(who: &AccountId) {
+	if Identity::registrars().is_empty() {
+		assert_ok!(Identity::add_registrar(RuntimeOrigin::root(), registrar().into()));
+	}
+	let info = IdentityInfo {
+		additional: Default::default(),
+		display: Data::Raw(b"name".to_vec().try_into().unwrap()),
+		legal: Data::None,
+		web: Data::None,
+		riot: Data::None,
+		email: Data::None,
+		pgp_fingerprint: None,
+		image: Data::None,
+		twitter: Data::None,
+	};
+	let hash = BlakeTwo256::hash_of(&info);
+	assert_ok!(Identity::set_identity(RuntimeOrigin::signed(who.clone()), Box::new(info)));
+	assert_ok!(Identity::provide_judgement(
+		RuntimeOrigin::signed(registrar()),
+		PoeRegistrar::get(),
+		who.clone().into(),
+		Judgement::KnownGood,
+		hash,
+	));
+}
+
+#[test]
+fn claimants_need_an_identity_before_the_registrar_exists() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(alice()), claim.clone()),
+			pallet_poe::Error::<Runtime>::IdentityRequired
+		);
+
+		// 登记员存在后，只放行其认可的账户
+		verify(&bob());
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(alice()), claim.clone()),
+			pallet_poe::Error::<Runtime>::IdentityRequired
+		);
+		verify(&alice());
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(alice()), claim));
+	});
+}
+
+#[test]
+fn transferring_a_claim_nft_transfers_the_claim() {
+	new_test_ext().execute_with(|| {
+		verify(&alice());
+		verify(&bob());
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(alice()), claim.clone()));
+
+		let item = Hash::from(sp_io::hashing::blake2_256(&claim));
+		assert_ok!(Nfts::transfer(
+			RuntimeOrigin::signed(alice()),
+			PoeNftCollection::get(),
+			item,
+			bob().into(),
+		));
+		assert_ok!(PoeModule::sync_claim_owner(RuntimeOrigin::signed(alice()), claim.clone()));
+
+		assert_eq!(PoeModule::proofs(bounded(&claim)), Some((bob(), 1)));
+	});
+}
+
+#[test]
+fn claim_calls_weigh_their_storage_accesses() {
+	new_test_ext().execute_with(|| {
+		// 运行时的存证钩子都是 `()`，权重只来自存证模块自身的读写
+		let claim = vec![0, 1];
+		let calls: Vec<RuntimeCall> = vec![
+			pallet_poe::Call::create_claim { claim: claim.clone() }.into(),
+			pallet_poe::Call::create_claim_with_content { content: claim.clone() }.into(),
+			pallet_poe::Call::transfer_claim { claim: claim.clone(), dest: bob() }.into(),
+			pallet_poe::Call::revoke_claim { claim: claim.clone() }.into(),
+			pallet_poe::Call::reveal_claim { claim: claim.clone(), salt: Default::default() }
+				.into(),
+			pallet_poe::Call::buy_claim { claim: claim.clone(), max_price: 1 }.into(),
+			pallet_poe::Call::submit_availability { results: vec![(claim.clone(), true)] }.into(),
+			pallet_poe::Call::add_attester { who: bob() }.into(),
+			pallet_poe::Call::remove_attester { who: bob() }.into(),
+			pallet_poe::Call::attest_claim {
+				claim: claim.clone(),
+				attestation_type: Default::default(),
+				expires_at: None,
+			}
+			.into(),
+			pallet_poe::Call::revoke_attestation { claim }.into(),
+			pallet_poe::Call::set_parameters { parameters: PoeModule::parameters() }.into(),
+			pallet_poe::Call::set_approval_for_all { operator: bob(), approved: true }.into(),
+			pallet_poe::Call::add_availability_signer { who: bob() }.into(),
+			pallet_poe::Call::remove_availability_signer { who: bob() }.into(),
+		];
+		for call in calls {
+			assert!(call.get_dispatch_info().weight.ref_time() > 0);
+		}
+	});
+}